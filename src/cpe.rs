@@ -0,0 +1,251 @@
+use anyhow::{anyhow, bail};
+use std::str::FromStr;
+
+const FORMATTED_PREFIX: &str = "cpe:2.3:";
+const URI_PREFIX: &str = "cpe:/";
+
+/// A parsed CPE, either from the 2.3 formatted string or the URI binding.
+///
+/// Logical values (ANY and NA) are represented as `None`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+pub struct Cpe {
+    pub part: Option<String>,
+    pub vendor: Option<String>,
+    pub product: Option<String>,
+    pub version: Option<String>,
+    pub update: Option<String>,
+    pub edition: Option<String>,
+    pub language: Option<String>,
+    pub sw_edition: Option<String>,
+    pub target_sw: Option<String>,
+    pub target_hw: Option<String>,
+    pub other: Option<String>,
+}
+
+impl FromStr for Cpe {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cpe = if let Some(formatted) = s.strip_prefix(FORMATTED_PREFIX) {
+            Self::from_formatted(formatted)?
+        } else if let Some(uri) = s.strip_prefix(URI_PREFIX) {
+            Self::from_uri(uri)?
+        } else {
+            bail!("must start with '{FORMATTED_PREFIX}' or '{URI_PREFIX}'");
+        };
+
+        if let Some(part) = &cpe.part
+            && !matches!(part.as_str(), "a" | "h" | "o")
+        {
+            bail!("invalid part '{part}', must be 'a', 'h' or 'o'");
+        }
+
+        Ok(cpe)
+    }
+}
+
+impl Cpe {
+    /// Parse the components of a 2.3 formatted string, after the prefix.
+    fn from_formatted(s: &str) -> anyhow::Result<Self> {
+        // split at unescaped colons, only unescaped values are logical ones (like `-`, but not `\-`)
+        let value = |value: String, escaped: bool| match value.as_str() {
+            "*" | "-" | "" if !escaped => None,
+            _ => Some(value),
+        };
+
+        let mut values = vec![];
+        let mut current = String::new();
+        let mut escaped = false;
+        let mut chars = s.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    current.push(chars.next().ok_or_else(|| anyhow!("trailing escape"))?);
+                    escaped = true;
+                }
+                ':' => values.push(value(
+                    std::mem::take(&mut current),
+                    std::mem::take(&mut escaped),
+                )),
+                c => current.push(c),
+            }
+        }
+        values.push(value(current, escaped));
+
+        if values.len() != 11 {
+            bail!("expected 11 components, found {}", values.len());
+        }
+
+        let mut values = values.into_iter();
+        let mut next = || values.next().flatten();
+
+        Ok(Self {
+            part: next(),
+            vendor: next(),
+            product: next(),
+            version: next(),
+            update: next(),
+            edition: next(),
+            language: next(),
+            sw_edition: next(),
+            target_sw: next(),
+            target_hw: next(),
+            other: next(),
+        })
+    }
+
+    /// Parse the components of a URI, after the prefix.
+    fn from_uri(s: &str) -> anyhow::Result<Self> {
+        let values = s.split(':').collect::<Vec<_>>();
+
+        if values.len() > 7 {
+            bail!("expected at most 7 components, found {}", values.len());
+        }
+
+        let value = |index: usize| values.get(index).map_or(Ok(None), |value| uri_value(value));
+
+        let mut cpe = Self {
+            part: value(0)?,
+            vendor: value(1)?,
+            product: value(2)?,
+            version: value(3)?,
+            update: value(4)?,
+            language: value(6)?,
+            ..Default::default()
+        };
+
+        // the edition may pack the extended attributes: ~edition~sw_edition~target_sw~target_hw~other
+        match values.get(5).and_then(|edition| edition.strip_prefix('~')) {
+            Some(packed) => {
+                let packed = packed.split('~').collect::<Vec<_>>();
+                if packed.len() != 5 {
+                    bail!(
+                        "expected 5 packed edition components, found {}",
+                        packed.len()
+                    );
+                }
+
+                cpe.edition = uri_value(packed[0])?;
+                cpe.sw_edition = uri_value(packed[1])?;
+                cpe.target_sw = uri_value(packed[2])?;
+                cpe.target_hw = uri_value(packed[3])?;
+                cpe.other = uri_value(packed[4])?;
+            }
+            None => cpe.edition = value(5)?,
+        }
+
+        Ok(cpe)
+    }
+}
+
+/// Decode a component of a URI. Only unencoded values are logical ones (like `-`, but not `%2d`).
+fn uri_value(value: &str) -> anyhow::Result<Option<String>> {
+    Ok(match value {
+        "" | "-" => None,
+        value => Some(urlencoding::decode(value)?.into_owned()),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn value(value: &str) -> Option<String> {
+        Some(value.into())
+    }
+
+    #[test]
+    fn formatted() {
+        let cpe: Cpe = "cpe:2.3:a:redhat:openssl:3.0.7:*:-:en:*:rhel9:x86_64:*"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            cpe,
+            Cpe {
+                part: value("a"),
+                vendor: value("redhat"),
+                product: value("openssl"),
+                version: value("3.0.7"),
+                update: None,
+                edition: None,
+                language: value("en"),
+                sw_edition: None,
+                target_sw: value("rhel9"),
+                target_hw: value("x86_64"),
+                other: None,
+            }
+        );
+    }
+
+    #[test]
+    fn formatted_escapes() {
+        let cpe: Cpe = r"cpe:2.3:a:ex\:ample:lib\\x:\-:\*:*:*:*:*:*:*"
+            .parse()
+            .unwrap();
+
+        assert_eq!(cpe.vendor, value("ex:ample"));
+        assert_eq!(cpe.product, value(r"lib\x"));
+        assert_eq!(cpe.version, value("-"));
+        assert_eq!(cpe.update, value("*"));
+        assert_eq!(cpe.edition, None);
+    }
+
+    #[test]
+    fn uri() {
+        let cpe: Cpe = "cpe:/a:ex%3aample:lib:1.0:-:%2d".parse().unwrap();
+
+        assert_eq!(
+            cpe,
+            Cpe {
+                part: value("a"),
+                vendor: value("ex:ample"),
+                product: value("lib"),
+                version: value("1.0"),
+                update: None,
+                edition: value("-"),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn uri_short() {
+        let cpe: Cpe = "cpe:/o:redhat:enterprise_linux".parse().unwrap();
+
+        assert_eq!(cpe.part, value("o"));
+        assert_eq!(cpe.vendor, value("redhat"));
+        assert_eq!(cpe.product, value("enterprise_linux"));
+        assert_eq!(cpe.version, None);
+    }
+
+    #[test]
+    fn uri_packed() {
+        let cpe: Cpe = "cpe:/a:ex:lib:1.0::~ed~-~linux~x64~%7eother:de"
+            .parse()
+            .unwrap();
+
+        assert_eq!(cpe.edition, value("ed"));
+        assert_eq!(cpe.sw_edition, None);
+        assert_eq!(cpe.target_sw, value("linux"));
+        assert_eq!(cpe.target_hw, value("x64"));
+        assert_eq!(cpe.other, value("~other"));
+        assert_eq!(cpe.language, value("de"));
+    }
+
+    #[test]
+    fn malformed() {
+        for cpe in [
+            "not a cpe",
+            "cpe:2.3:a:ex:lib",
+            "cpe:2.3:x:ex:lib:*:*:*:*:*:*:*:*",
+            r"cpe:2.3:a:ex:lib:*:*:*:*:*:*:*:\",
+            "cpe:/a:ex:lib:1.0:u:e:en:extra",
+            "cpe:/a:ex:lib:1.0::~ed~x",
+            "cpe:/q:ex",
+        ] {
+            assert!(cpe.parse::<Cpe>().is_err(), "{cpe}");
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use super::{Graph, cpes};
+
+/// Separator for joining multiple values into a single column
+const SEPARATOR: &str = "|";
+
+fn join<'a>(values: impl IntoIterator<Item = &'a String>) -> String {
+    values
+        .into_iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(SEPARATOR)
+}
+
+impl Graph {
+    /// Render the list of nodes and the list of edges as CSV.
+    pub fn render_csv<N, E>(self, nodes: &mut N, edges: &mut E) -> Result<(), anyhow::Error>
+    where
+        N: std::io::Write,
+        E: std::io::Write,
+    {
+        let mut w = csv::Writer::from_writer(nodes);
+        w.write_record([
+            "key",
+            "sbom_id",
+            "node_id",
+            "name",
+            "document_id",
+            "published",
+            "product_name",
+            "product_version",
+            "purls",
+            "cpes",
+            "cpe_vendors",
+            "cpe_products",
+            "cpe_versions",
+            "sources",
+            "sbom_ids",
+            "document_ids",
+        ])?;
+
+        for (sbom, sbom_nodes) in self.sorted_nodes() {
+            let sbom_doc = self.sboms.get(sbom).expect("SBOM not found");
+
+            for node in sbom_nodes {
+                let key = node.as_key();
+                let cpes = cpes(node);
+                w.write_record([
+                    &key.to_string(),
+                    sbom,
+                    &node.node_id,
+                    &node.name,
+                    &sbom_doc.document_id,
+                    &sbom_doc.published,
+                    node.product_name.as_deref().unwrap_or_default(),
+                    node.product_version.as_deref().unwrap_or_default(),
+                    &join(&node.purl),
+                    &join(&node.cpe),
+                    &join(cpes.iter().filter_map(|c| c.vendor.as_ref())),
+                    &join(cpes.iter().filter_map(|c| c.product.as_ref())),
+                    &join(cpes.iter().filter_map(|c| c.version.as_ref())),
+                    &join(self.node_sources(&key)),
+                    &join(&self.node_sboms(&key)),
+                    &join(self.node_documents(&key)),
+                ])?;
+            }
+        }
+
+        w.flush()?;
+
+        let mut w = csv::Writer::from_writer(edges);
+        w.write_record(["source", "target", "relationship", "sources"])?;
+
+        for ((from, to), rel) in self.sorted_relationships() {
+            w.write_record([
+                &from.to_string(),
+                &to.to_string(),
+                rel,
+                &join(self.relationship_sources(from, to)),
+            ])?;
+        }
+
+        w.flush()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::graph::test::{graph, node, with, with_purls};
+
+    #[test]
+    fn render() {
+        let mut a = with_purls("s1", "a", &["pkg:npm/a@1", "pkg:npm/a@1?x=y"]);
+        a.cpe.push("cpe:/a:ex:a:1".into());
+        a.name = "a, \"quoted\"".into();
+        let mut nodes = vec![];
+        let mut edges = vec![];
+        graph([with(a, [node("s1", "b")])])
+            .render_csv(&mut nodes, &mut edges)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(nodes).unwrap(),
+            r#"key,sbom_id,node_id,name,document_id,published,product_name,product_version,purls,cpes,cpe_vendors,cpe_products,cpe_versions,sources,sbom_ids,document_ids
+s1--a,s1,a,"a, ""quoted""",doc-s1,2025-01-01T00:00:00Z,,,pkg:npm/a@1|pkg:npm/a@1?x=y,cpe:/a:ex:a:1,ex,a,1,test.json,s1,doc-s1
+s1--b,s1,b,b,doc-s1,2025-01-01T00:00:00Z,,,,,,,,test.json,s1,doc-s1
+"#
+        );
+        assert_eq!(
+            String::from_utf8(edges).unwrap(),
+            "source,target,relationship,sources\ns1--a,s1--b,dependency_of,test.json\n"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use super::{Graph, cpes};
+
+/// Create a quoted Cypher string literal
+fn string(s: impl AsRef<str>) -> String {
+    format!(
+        "'{}'",
+        s.as_ref()
+            .replace('\\', "\\\\")
+            .replace('\'', "\\'")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")
+    )
+}
+
+fn list<'a>(values: impl IntoIterator<Item = &'a String>) -> String {
+    let values = values.into_iter().map(string).collect::<Vec<_>>();
+    format!("[{}]", values.join(", "))
+}
+
+fn optional(value: Option<&String>) -> String {
+    value.map(string).unwrap_or_else(|| "null".to_string())
+}
+
+/// Convert a relationship (like `dependency_of`, or `DependencyOf`) into a relationship type
+/// (like `DEPENDENCY_OF`).
+fn relationship_type(rel: &str) -> String {
+    let mut result = String::new();
+    let mut prev_lower = false;
+
+    for c in rel.chars() {
+        if c.is_alphanumeric() {
+            if c.is_uppercase() && prev_lower {
+                result.push('_');
+            }
+            prev_lower = c.is_lowercase() || c.is_numeric();
+            result.extend(c.to_uppercase());
+        } else {
+            if !result.is_empty() && !result.ends_with('_') {
+                result.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+
+    let result = result.trim_end_matches('_');
+    match result.is_empty() {
+        true => "RELATED_TO".to_string(),
+        false => format!("`{}`", result.replace('`', "")),
+    }
+}
+
+/// Merge new values into the existing list of a property, without duplicates
+fn merge_list(var: &str, property: &str, values: String) -> String {
+    format!(
+        "{var}.{property} = coalesce({var}.{property}, []) + [x IN {values} WHERE NOT x IN coalesce({var}.{property}, [])]"
+    )
+}
+
+impl Graph {
+    /// Render a Cypher script, importing the graph into a graph database.
+    ///
+    /// All statements use `MERGE`, so running the script multiple times, or running scripts of
+    /// different analysis results, is safe.
+    pub fn render_cypher<W>(self, w: &mut W) -> Result<(), std::io::Error>
+    where
+        W: std::io::Write,
+    {
+        writeln!(
+            w,
+            "CREATE CONSTRAINT IF NOT EXISTS FOR (s:Sbom) REQUIRE s.id IS UNIQUE;"
+        )?;
+        writeln!(
+            w,
+            "CREATE CONSTRAINT IF NOT EXISTS FOR (c:Component) REQUIRE c.key IS UNIQUE;"
+        )?;
+        writeln!(w)?;
+
+        for (sbom, nodes) in self.sorted_nodes() {
+            let sbom_doc = self.sboms.get(sbom).expect("SBOM not found");
+
+            writeln!(
+                w,
+                "MERGE (s:Sbom {{id: {id}}}) SET s.document_id = {document_id}, s.published = {published};",
+                id = string(sbom),
+                document_id = string(&sbom_doc.document_id),
+                published = string(&sbom_doc.published),
+            )?;
+
+            for node in nodes {
+                let key = node.as_key();
+                let cpes = cpes(node);
+
+                let props = format!(
+                    "c.sbom_id = {sbom_id}, c.node_id = {node_id}, c.name = {name}, c.purl = {purl}, c.cpe = {cpe}, c.cpe_vendor = {cpe_vendor}, c.cpe_product = {cpe_product}, c.cpe_version = {cpe_version}, c.product_name = {product_name}, c.product_version = {product_version}, {sources}, {sbom_ids}, {document_ids}",
+                    sbom_id = string(sbom),
+                    node_id = string(&node.node_id),
+                    name = string(&node.name),
+                    purl = list(&node.purl),
+                    cpe = list(&node.cpe),
+                    cpe_vendor = list(cpes.iter().filter_map(|c| c.vendor.as_ref())),
+                    cpe_product = list(cpes.iter().filter_map(|c| c.product.as_ref())),
+                    cpe_version = list(cpes.iter().filter_map(|c| c.version.as_ref())),
+                    product_name = optional(node.product_name.as_ref()),
+                    product_version = optional(node.product_version.as_ref()),
+                    sources = merge_list("c", "sources", list(self.node_sources(&key))),
+                    sbom_ids = merge_list("c", "sbom_ids", list(&self.node_sboms(&key))),
+                    document_ids = merge_list("c", "document_ids", list(self.node_documents(&key))),
+                );
+
+                writeln!(
+                    w,
+                    "MERGE (c:Component {{key: {key}}}) SET {props};",
+                    key = string(key.to_string()),
+                )?;
+                writeln!(
+                    w,
+                    "MATCH (s:Sbom {{id: {sbom}}}), (c:Component {{key: {key}}}) MERGE (s)-[:CONTAINS]->(c);",
+                    sbom = string(sbom),
+                    key = string(key.to_string()),
+                )?;
+            }
+
+            writeln!(w)?;
+        }
+
+        for ((from, to), rel) in self.sorted_relationships() {
+            writeln!(
+                w,
+                "MATCH (a:Component {{key: {from}}}), (b:Component {{key: {to}}}) MERGE (a)-[r:{type}]->(b) SET r.relationship = {rel}, {sources};",
+                from = string(from.to_string()),
+                to = string(to.to_string()),
+                r#type = relationship_type(rel),
+                rel = string(rel),
+                sources = merge_list("r", "sources", list(self.relationship_sources(from, to))),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::test::{graph, node, with};
+
+    #[test]
+    fn relationship_types() {
+        assert_eq!(relationship_type("dependency_of"), "`DEPENDENCY_OF`");
+        assert_eq!(relationship_type("DependencyOf"), "`DEPENDENCY_OF`");
+        assert_eq!(relationship_type("a-b c"), "`A_B_C`");
+        assert_eq!(relationship_type("variant2Of"), "`VARIANT2_OF`");
+        assert_eq!(relationship_type(""), "RELATED_TO");
+        assert_eq!(relationship_type("--"), "RELATED_TO");
+    }
+
+    #[test]
+    fn relationship_type_injection() {
+        assert_eq!(
+            relationship_type("x`]->(b) DETACH DELETE b //"),
+            "`X_B_DETACH_DELETE_B`"
+        );
+        assert_eq!(relationship_type("`"), "RELATED_TO");
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(string("plain"), "'plain'");
+        assert_eq!(string(r"it's a \ test"), r"'it\'s a \\ test'");
+        assert_eq!(string("a\r\nb"), r"'a\r\nb'");
+        assert_eq!(list(&["a".to_string(), "b'".to_string()]), r"['a', 'b\'']");
+        assert_eq!(optional(None), "null");
+    }
+
+    #[test]
+    fn render() {
+        let mut output = vec![];
+        graph([with(node("s1", "a"), [node("s1", "b")])])
+            .render_cypher(&mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("MERGE (s:Sbom {id: 's1'})"));
+        assert!(output.contains("MERGE (c:Component {key: 's1--a'})"));
+        assert!(output.contains(
+            "MATCH (a:Component {key: 's1--a'}), (b:Component {key: 's1--b'}) MERGE (a)-[r:`DEPENDENCY_OF`]->(b) SET r.relationship = 'dependency_of'"
+        ));
+    }
+}
@@ -0,0 +1,884 @@
+use super::{Graph, cpes};
+use crate::model::Key;
+use anyhow::bail;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::io::{IsTerminal, stdout};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Focus {
+    Sboms,
+    Components,
+    Relationships,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Direction {
+    Outgoing,
+    Incoming,
+}
+
+/// A row in the component tree
+struct Row {
+    /// The path from the root to the component
+    path: Vec<Key>,
+    /// The relationship to the parent in the tree
+    relationship: Option<String>,
+    expandable: bool,
+    /// The component is already part of the path
+    cycle: bool,
+}
+
+/// A relationship of the selected component
+struct Link {
+    direction: Direction,
+    key: Key,
+    relationship: String,
+}
+
+struct Search {
+    query: String,
+    results: Vec<Key>,
+    state: ListState,
+}
+
+struct App {
+    graph: Graph,
+
+    sboms: Vec<String>,
+    roots: BTreeMap<String, Vec<Key>>,
+    /// Outgoing relationships, within the same SBOM
+    children: BTreeMap<Key, Vec<(Key, String)>>,
+    outgoing: BTreeMap<Key, Vec<(Key, String)>>,
+    incoming: BTreeMap<Key, Vec<(Key, String)>>,
+
+    focus: Focus,
+    sbom_state: ListState,
+    expanded: HashSet<Vec<Key>>,
+    rows: Vec<Row>,
+    row_state: ListState,
+    links: Vec<Link>,
+    link_state: ListState,
+    search: Option<Search>,
+    message: Option<String>,
+}
+
+impl App {
+    fn new(graph: Graph) -> Self {
+        let mut sboms = graph.nodes.keys().cloned().collect::<Vec<_>>();
+        sboms.sort();
+
+        let mut children = BTreeMap::<Key, Vec<(Key, String)>>::new();
+        let mut outgoing = BTreeMap::<Key, Vec<(Key, String)>>::new();
+        let mut incoming = BTreeMap::<Key, Vec<(Key, String)>>::new();
+
+        for ((from, to), rel) in graph.sorted_relationships() {
+            if graph.node(from).is_none() || graph.node(to).is_none() {
+                continue;
+            }
+            if from.sbom == to.sbom {
+                children
+                    .entry(from.clone())
+                    .or_default()
+                    .push((to.clone(), rel.clone()));
+            }
+            outgoing
+                .entry(from.clone())
+                .or_default()
+                .push((to.clone(), rel.clone()));
+            incoming
+                .entry(to.clone())
+                .or_default()
+                .push((from.clone(), rel.clone()));
+        }
+
+        // roots are components without a parent in the same SBOM. Components only reachable
+        // through cycles become roots too, so that every component can be found in the tree.
+        let mut roots = BTreeMap::new();
+        for (sbom, nodes) in graph.sorted_nodes() {
+            let keys = nodes.iter().map(|node| node.as_key()).collect::<Vec<_>>();
+            let mut sbom_roots = keys
+                .iter()
+                .filter(|key| {
+                    !incoming
+                        .get(key)
+                        .is_some_and(|parents| parents.iter().any(|(p, _)| &p.sbom == sbom))
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let mut reachable = HashSet::new();
+            mark_reachable(&children, &mut reachable, &sbom_roots);
+            for key in &keys {
+                if !reachable.contains(key) {
+                    sbom_roots.push(key.clone());
+                    mark_reachable(&children, &mut reachable, std::slice::from_ref(key));
+                }
+            }
+
+            roots.insert(sbom.clone(), sbom_roots);
+        }
+
+        let mut app = Self {
+            graph,
+            sboms,
+            roots,
+            children,
+            outgoing,
+            incoming,
+            focus: Focus::Sboms,
+            sbom_state: ListState::default(),
+            expanded: HashSet::new(),
+            rows: vec![],
+            row_state: ListState::default(),
+            links: vec![],
+            link_state: ListState::default(),
+            search: None,
+            message: None,
+        };
+
+        if !app.sboms.is_empty() {
+            app.sbom_state.select(Some(0));
+        }
+        app.rebuild();
+
+        app
+    }
+
+    fn selected_sbom(&self) -> Option<&String> {
+        self.sbom_state.selected().and_then(|i| self.sboms.get(i))
+    }
+
+    fn selected_key(&self) -> Option<&Key> {
+        match &self.search {
+            Some(search) => search.state.selected().and_then(|i| search.results.get(i)),
+            None => self
+                .row_state
+                .selected()
+                .and_then(|i| self.rows.get(i))
+                .and_then(|row| row.path.last()),
+        }
+    }
+
+    /// Rebuild the rows of the component tree, and the relationships of the selection.
+    fn rebuild(&mut self) {
+        self.rows.clear();
+
+        if let Some(sbom) = self.selected_sbom().cloned() {
+            let roots = self.roots.get(&sbom).cloned().unwrap_or_default();
+            for root in roots {
+                self.push_rows(vec![root], None);
+            }
+        }
+
+        match (self.row_state.selected(), self.rows.len()) {
+            (_, 0) => self.row_state.select(None),
+            (None, _) => self.row_state.select(Some(0)),
+            (Some(i), len) if i >= len => self.row_state.select(Some(len - 1)),
+            _ => {}
+        }
+
+        self.rebuild_links();
+    }
+
+    fn push_rows(&mut self, path: Vec<Key>, relationship: Option<String>) {
+        let key = path.last().expect("path must not be empty").clone();
+        let cycle = path[..path.len() - 1].contains(&key);
+        let children = self.children.get(&key).cloned().unwrap_or_default();
+        let expanded = !cycle && self.expanded.contains(&path);
+
+        self.rows.push(Row {
+            path: path.clone(),
+            relationship,
+            expandable: !cycle && !children.is_empty(),
+            cycle,
+        });
+
+        if expanded {
+            for (child, rel) in children {
+                let mut path = path.clone();
+                path.push(child);
+                self.push_rows(path, Some(rel));
+            }
+        }
+    }
+
+    fn rebuild_links(&mut self) {
+        self.links.clear();
+
+        if let Some(key) = self.selected_key().cloned() {
+            for (other, rel) in self.outgoing.get(&key).into_iter().flatten() {
+                self.links.push(Link {
+                    direction: Direction::Outgoing,
+                    key: other.clone(),
+                    relationship: rel.clone(),
+                });
+            }
+            for (other, rel) in self.incoming.get(&key).into_iter().flatten() {
+                self.links.push(Link {
+                    direction: Direction::Incoming,
+                    key: other.clone(),
+                    relationship: rel.clone(),
+                });
+            }
+        }
+
+        self.link_state
+            .select((!self.links.is_empty()).then_some(0));
+    }
+
+    /// Select a component in the tree, expanding the path leading to it.
+    fn reveal(&mut self, key: &Key) {
+        let Some(index) = self.sboms.iter().position(|sbom| sbom == &key.sbom) else {
+            return;
+        };
+        self.sbom_state.select(Some(index));
+
+        // breadth first, finding the shortest path from one of the roots
+        let mut queue = self
+            .roots
+            .get(&key.sbom)
+            .into_iter()
+            .flatten()
+            .map(|root| vec![root.clone()])
+            .collect::<VecDeque<_>>();
+        let mut seen = HashSet::new();
+        let mut found = None;
+
+        while let Some(path) = queue.pop_front() {
+            let last = path.last().expect("path must not be empty");
+            if last == key {
+                found = Some(path);
+                break;
+            }
+            if !seen.insert(last.clone()) {
+                continue;
+            }
+            for (child, _) in self.children.get(last).into_iter().flatten() {
+                let mut next = path.clone();
+                next.push(child.clone());
+                queue.push_back(next);
+            }
+        }
+
+        let Some(path) = found else {
+            self.message = Some(format!("Unable to find {key} in the component tree"));
+            return;
+        };
+
+        for len in 1..path.len() {
+            self.expanded.insert(path[..len].to_vec());
+        }
+
+        self.search = None;
+        self.row_state.select(None);
+        self.rebuild();
+        self.row_state
+            .select(self.rows.iter().position(|row| row.path == path));
+        self.rebuild_links();
+        self.focus = Focus::Components;
+    }
+
+    fn update_search(&mut self) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+
+        let query = search.query.to_lowercase();
+        search.results = self
+            .graph
+            .sorted_nodes()
+            .into_iter()
+            .flat_map(|(_, nodes)| nodes)
+            .filter(|node| {
+                !query.is_empty()
+                    && (node.name.to_lowercase().contains(&query)
+                        || node
+                            .purl
+                            .iter()
+                            .any(|purl| purl.to_lowercase().contains(&query)))
+            })
+            .map(|node| node.as_key())
+            .collect();
+        search
+            .state
+            .select((!search.results.is_empty()).then_some(0));
+
+        self.rebuild_links();
+    }
+
+    /// Handle a key event, returning `false` when the application should quit.
+    fn handle(&mut self, key: KeyEvent) -> bool {
+        self.message = None;
+
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return false;
+        }
+
+        if let Some(search) = &mut self.search {
+            match key.code {
+                KeyCode::Esc => {
+                    self.search = None;
+                    self.rebuild_links();
+                }
+                KeyCode::Enter => {
+                    if let Some(key) = self.selected_key().cloned() {
+                        self.reveal(&key);
+                    }
+                }
+                KeyCode::Up => {
+                    search.state.select_previous();
+                    self.rebuild_links();
+                }
+                KeyCode::Down => {
+                    search.state.select_next();
+                    self.rebuild_links();
+                }
+                KeyCode::Backspace => {
+                    search.query.pop();
+                    self.update_search();
+                }
+                KeyCode::Char(c) => {
+                    search.query.push(c);
+                    self.update_search();
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('/') => {
+                self.search = Some(Search {
+                    query: String::new(),
+                    results: vec![],
+                    state: ListState::default(),
+                });
+                self.rebuild_links();
+            }
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Sboms => Focus::Components,
+                    Focus::Components => Focus::Relationships,
+                    Focus::Relationships => Focus::Sboms,
+                }
+            }
+            KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::Sboms => Focus::Relationships,
+                    Focus::Components => Focus::Sboms,
+                    Focus::Relationships => Focus::Components,
+                }
+            }
+            code => match self.focus {
+                Focus::Sboms => self.handle_sboms(code),
+                Focus::Components => self.handle_components(code),
+                Focus::Relationships => self.handle_relationships(code),
+            },
+        }
+
+        true
+    }
+
+    fn handle_sboms(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.sbom_state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.sbom_state.select_next(),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+                self.focus = Focus::Components;
+                return;
+            }
+            _ => return,
+        }
+
+        self.row_state.select(None);
+        self.rebuild();
+    }
+
+    fn handle_components(&mut self, code: KeyCode) {
+        let Some(index) = self.row_state.selected() else {
+            if matches!(code, KeyCode::Left | KeyCode::Char('h')) {
+                self.focus = Focus::Sboms;
+            }
+            return;
+        };
+
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.row_state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.row_state.select_next(),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter => {
+                let row = &self.rows[index];
+                if row.expandable && !self.expanded.remove(&row.path) {
+                    self.expanded.insert(row.path.clone());
+                } else if !row.expandable && code == KeyCode::Enter {
+                    self.focus = Focus::Relationships;
+                }
+                self.rebuild();
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                let path = self.rows[index].path.clone();
+                if !self.expanded.remove(&path) {
+                    if path.len() > 1 {
+                        let parent = &path[..path.len() - 1];
+                        self.row_state
+                            .select(self.rows.iter().position(|row| row.path == parent));
+                    } else {
+                        self.focus = Focus::Sboms;
+                    }
+                }
+                self.rebuild();
+            }
+            _ => return,
+        }
+
+        self.rebuild_links();
+    }
+
+    fn handle_relationships(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.link_state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.link_state.select_next(),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+                if let Some(link) = self.link_state.selected().and_then(|i| self.links.get(i)) {
+                    let key = link.key.clone();
+                    self.reveal(&key);
+                }
+            }
+            KeyCode::Left | KeyCode::Char('h') => self.focus = Focus::Components,
+            _ => {}
+        }
+    }
+
+    fn name(&self, key: &Key) -> String {
+        self.graph
+            .node(key)
+            .map(|node| node.name.clone())
+            .unwrap_or_else(|| key.to_string())
+    }
+
+    fn sbom_label(&self, sbom: &str) -> String {
+        match self.graph.sboms.get(sbom) {
+            Some(doc) => format!("{} / {} / {sbom}", doc.document_id, doc.published),
+            None => sbom.to_string(),
+        }
+    }
+
+    fn block(&self, title: &str, focus: Focus) -> Block<'static> {
+        let block = Block::bordered().title(title.to_string());
+        match self.focus == focus && self.search.is_none() {
+            true => block.border_style(Style::new().fg(Color::Cyan)),
+            false => block,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .areas(main);
+        let [sboms, components] =
+            Layout::vertical([Constraint::Percentage(30), Constraint::Percentage(70)]).areas(left);
+        let [details, relationships] =
+            Layout::vertical([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(right);
+
+        self.draw_sboms(frame, sboms);
+        match self.search.is_some() {
+            true => self.draw_search(frame, components),
+            false => self.draw_components(frame, components),
+        }
+        self.draw_details(frame, details);
+        self.draw_relationships(frame, relationships);
+
+        let status_line = match (&self.message, &self.search) {
+            (Some(message), _) => Line::from(message.clone()).red(),
+            (None, Some(_)) => Line::from("Type to search · ↑/↓ select · Enter jump · Esc cancel"),
+            (None, None) => Line::from(
+                "q quit · Tab switch pane · ↑/↓ move · →/Enter expand or jump · ← collapse · / search",
+            ),
+        };
+        frame.render_widget(status_line.dim(), status);
+    }
+
+    fn draw_sboms(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .sboms
+            .iter()
+            .map(|sbom| ListItem::new(self.sbom_label(sbom)))
+            .collect::<Vec<_>>();
+
+        let list = List::new(items)
+            .block(self.block("SBOMs", Focus::Sboms))
+            .highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(list, area, &mut self.sbom_state);
+    }
+
+    fn draw_components(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .rows
+            .iter()
+            .map(|row| {
+                let key = row.path.last().expect("path must not be empty");
+                let marker = match (row.expandable, self.expanded.contains(&row.path)) {
+                    (false, _) => "  ",
+                    (true, false) => "▸ ",
+                    (true, true) => "▾ ",
+                };
+
+                let mut spans = vec![
+                    Span::raw("  ".repeat(row.path.len() - 1)),
+                    Span::raw(marker),
+                    Span::raw(self.name(key)),
+                ];
+                if let Some(rel) = &row.relationship {
+                    spans.push(Span::raw(format!(" [{rel}]")).dim());
+                }
+                if row.cycle {
+                    spans.push(Span::raw(" ↻").yellow());
+                }
+
+                ListItem::new(Line::from(spans))
+            })
+            .collect::<Vec<_>>();
+
+        let list = List::new(items)
+            .block(self.block("Components", Focus::Components))
+            .highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(list, area, &mut self.row_state);
+    }
+
+    fn draw_search(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(search) = &self.search else {
+            return;
+        };
+
+        let items = search
+            .results
+            .iter()
+            .map(|key| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(self.name(key)),
+                    Span::raw(format!(" ({})", self.sbom_label(&key.sbom))).dim(),
+                ]))
+            })
+            .collect::<Vec<_>>();
+
+        let title = format!(
+            "Search: {}▏ ({} results)",
+            search.query,
+            search.results.len()
+        );
+        let list = List::new(items)
+            .block(
+                Block::bordered()
+                    .title(title)
+                    .border_style(Style::new().fg(Color::Cyan)),
+            )
+            .highlight_style(Style::new().reversed());
+
+        if let Some(search) = &mut self.search {
+            frame.render_stateful_widget(list, area, &mut search.state);
+        }
+    }
+
+    fn draw_details(&self, frame: &mut Frame, area: Rect) {
+        let mut lines = vec![];
+
+        if let Some(node) = self.selected_key().and_then(|key| self.graph.node(key)) {
+            fn field<'a>(lines: &mut Vec<Line<'a>>, name: &'a str, values: &[&str]) {
+                for value in values {
+                    lines.push(Line::from(vec![
+                        Span::raw(format!("{name:<16}")).bold(),
+                        Span::raw(value.to_string()),
+                    ]));
+                }
+            }
+
+            let sbom = self.graph.sboms.get(&node.sbom_id);
+            let sources = self
+                .graph
+                .node_sources(&node.as_key())
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+
+            field(&mut lines, "Name", &[&node.name]);
+            field(&mut lines, "Node ID", &[&node.node_id]);
+            field(&mut lines, "SBOM ID", &[&node.sbom_id]);
+            if let Some(sbom) = sbom {
+                field(&mut lines, "Document ID", &[&sbom.document_id]);
+                field(&mut lines, "Published", &[&sbom.published]);
+            }
+            field(
+                &mut lines,
+                "Product name",
+                &node.product_name.as_deref().into_iter().collect::<Vec<_>>(),
+            );
+            field(
+                &mut lines,
+                "Product version",
+                &node
+                    .product_version
+                    .as_deref()
+                    .into_iter()
+                    .collect::<Vec<_>>(),
+            );
+            field(&mut lines, "PURL", &strs(&node.purl));
+            field(&mut lines, "CPE", &strs(&node.cpe));
+            let cpes = cpes(node);
+            for (name, values) in [
+                (
+                    "CPE vendor",
+                    cpes.iter()
+                        .filter_map(|c| c.vendor.as_deref())
+                        .collect::<Vec<_>>(),
+                ),
+                (
+                    "CPE product",
+                    cpes.iter().filter_map(|c| c.product.as_deref()).collect(),
+                ),
+                (
+                    "CPE version",
+                    cpes.iter().filter_map(|c| c.version.as_deref()).collect(),
+                ),
+            ] {
+                field(&mut lines, name, &values);
+            }
+            field(&mut lines, "Source", &sources);
+
+            let sboms = self.graph.node_sboms(&node.as_key());
+            if sboms.len() > 1 {
+                let sboms = sboms.iter().map(String::as_str).collect::<Vec<_>>();
+                field(&mut lines, "Merged SBOMs", &sboms);
+            }
+        }
+
+        let paragraph = Paragraph::new(lines)
+            .block(Block::bordered().title("Details"))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, area);
+    }
+
+    fn draw_relationships(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .links
+            .iter()
+            .map(|link| {
+                let arrow = match link.direction {
+                    Direction::Outgoing => "→",
+                    Direction::Incoming => "←",
+                };
+                let mut spans = vec![
+                    Span::raw(format!("{arrow} ")),
+                    Span::raw(format!("[{}] ", link.relationship)).dim(),
+                    Span::raw(self.name(&link.key)),
+                ];
+                if Some(&link.key.sbom) != self.selected_key().map(|key| &key.sbom) {
+                    spans.push(Span::raw(format!(" ({})", self.sbom_label(&link.key.sbom))).dim());
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect::<Vec<_>>();
+
+        let list = List::new(items)
+            .block(self.block("Relationships", Focus::Relationships))
+            .highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(list, area, &mut self.link_state);
+    }
+}
+
+fn mark_reachable(
+    children: &BTreeMap<Key, Vec<(Key, String)>>,
+    reachable: &mut HashSet<Key>,
+    start: &[Key],
+) {
+    let mut queue = start.iter().cloned().collect::<VecDeque<_>>();
+    while let Some(next) = queue.pop_front() {
+        if reachable.insert(next.clone()) {
+            queue.extend(
+                children
+                    .get(&next)
+                    .into_iter()
+                    .flatten()
+                    .map(|(k, _)| k.clone()),
+            );
+        }
+    }
+}
+
+fn strs(values: &[String]) -> Vec<&str> {
+    values.iter().map(String::as_str).collect()
+}
+
+fn run(terminal: &mut DefaultTerminal, mut app: App) -> anyhow::Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && !app.handle(key)
+        {
+            return Ok(());
+        }
+    }
+}
+
+impl Graph {
+    /// Explore the graph in an interactive terminal UI.
+    pub fn explore(self) -> anyhow::Result<()> {
+        if !stdout().is_terminal() {
+            bail!("exploring the graph requires a terminal");
+        }
+
+        let app = App::new(self);
+
+        let mut terminal = ratatui::try_init()?;
+        let result = run(&mut terminal, app);
+        ratatui::restore();
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::test::{graph, key, node, with};
+
+    /// s1: a → b → c → b, with a link from c to s2, which only has the cycle x → y → x
+    fn app() -> App {
+        App::new(graph([
+            with(
+                node("s1", "a"),
+                [with(
+                    node("s1", "b"),
+                    [with(node("s1", "c"), [node("s1", "b"), node("s2", "x")])],
+                )],
+            ),
+            with(node("s2", "x"), [with(node("s2", "y"), [node("s2", "x")])]),
+        ]))
+    }
+
+    fn press(app: &mut App, codes: impl IntoIterator<Item = KeyCode>) {
+        for code in codes {
+            assert!(app.handle(KeyEvent::from(code)));
+        }
+    }
+
+    /// The rows of the tree, indented by depth, with markers for cycles
+    fn rows(app: &App) -> Vec<String> {
+        app.rows
+            .iter()
+            .map(|row| {
+                format!(
+                    "{}{}{}",
+                    "  ".repeat(row.path.len() - 1),
+                    row.path.last().unwrap().node,
+                    if row.cycle { " ↻" } else { "" }
+                )
+            })
+            .collect()
+    }
+
+    fn links(app: &App) -> Vec<String> {
+        app.links
+            .iter()
+            .map(|link| match link.direction {
+                Direction::Outgoing => format!("→ {}", link.key),
+                Direction::Incoming => format!("← {}", link.key),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn roots() {
+        let app = app();
+        assert_eq!(app.sboms, vec!["s1", "s2"]);
+        assert_eq!(app.roots["s1"], vec![key("s1", "a")]);
+        // only reachable through a cycle
+        assert_eq!(app.roots["s2"], vec![key("s2", "x")]);
+        // cross SBOM relationships are no children
+        assert!(
+            app.children[&key("s1", "c")]
+                .iter()
+                .all(|(child, _)| child.sbom == "s1")
+        );
+    }
+
+    #[test]
+    fn expand_and_collapse() {
+        let mut app = app();
+        assert_eq!(rows(&app), vec!["a"]);
+
+        press(&mut app, [KeyCode::Tab, KeyCode::Right]);
+        assert_eq!(rows(&app), vec!["a", "  b"]);
+
+        press(
+            &mut app,
+            [KeyCode::Down, KeyCode::Right, KeyCode::Down, KeyCode::Right],
+        );
+        assert_eq!(rows(&app), vec!["a", "  b", "    c", "      b ↻"]);
+        assert!(!app.rows[3].expandable);
+
+        // collapsing a leaf selects its parent, collapsing the parent hides its children
+        press(&mut app, [KeyCode::Down, KeyCode::Left]);
+        assert_eq!(app.row_state.selected(), Some(2));
+        press(&mut app, [KeyCode::Left]);
+        assert_eq!(rows(&app), vec!["a", "  b", "    c"]);
+    }
+
+    #[test]
+    fn select_sbom() {
+        let mut app = app();
+        press(&mut app, [KeyCode::Down]);
+        assert_eq!(app.selected_sbom().map(String::as_str), Some("s2"));
+        assert_eq!(rows(&app), vec!["x"]);
+        assert_eq!(links(&app), vec!["→ s2--y", "← s1--c", "← s2--y"]);
+    }
+
+    #[test]
+    fn follow_relationship() {
+        let mut app = app();
+        app.reveal(&key("s1", "c"));
+        assert_eq!(rows(&app), vec!["a", "  b", "    c"]);
+        assert_eq!(app.selected_key(), Some(&key("s1", "c")));
+        assert_eq!(links(&app), vec!["→ s1--b", "→ s2--x", "← s1--b"]);
+
+        // jump to the component of the other SBOM
+        app.focus = Focus::Relationships;
+        press(&mut app, [KeyCode::Down, KeyCode::Enter]);
+        assert_eq!(app.selected_sbom().map(String::as_str), Some("s2"));
+        assert_eq!(app.selected_key(), Some(&key("s2", "x")));
+        assert_eq!(app.focus, Focus::Components);
+    }
+
+    #[test]
+    fn search() {
+        let mut app = app();
+        press(&mut app, [KeyCode::Char('/'), KeyCode::Char('Y')]);
+        let results = &app.search.as_ref().unwrap().results;
+        assert_eq!(results, &vec![key("s2", "y")]);
+        assert_eq!(links(&app), vec!["→ s2--x", "← s2--x"]);
+
+        press(&mut app, [KeyCode::Enter]);
+        assert!(app.search.is_none());
+        assert_eq!(rows(&app), vec!["x", "  y"]);
+        assert_eq!(app.selected_key(), Some(&key("s2", "y")));
+    }
+
+    #[test]
+    fn quit() {
+        let mut app = app();
+        press(
+            &mut app,
+            [KeyCode::Char('/'), KeyCode::Char('q'), KeyCode::Esc],
+        );
+        assert!(!app.handle(KeyEvent::from(KeyCode::Char('q'))));
+        assert!(!app.handle(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)));
+    }
+}
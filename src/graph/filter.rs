@@ -0,0 +1,392 @@
+use super::{Graph, cpes, purls};
+use crate::model::{Key, Node};
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+const REGEX_PREFIX: &str = "regex:";
+
+/// A pattern, matching a component by its name, PURL or CPE
+#[derive(Clone, Debug)]
+pub enum Pattern {
+    /// A glob, which must match the full value
+    Glob(GlobMatcher),
+    /// A regular expression, which may match any part of the value
+    Regex(Regex),
+}
+
+impl FromStr for Pattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.strip_prefix(REGEX_PREFIX) {
+            Some(regex) => Self::Regex(Regex::new(regex)?),
+            None => Self::Glob(Glob::new(s)?.compile_matcher()),
+        })
+    }
+}
+
+impl Pattern {
+    pub fn is_match(&self, value: &str) -> bool {
+        match self {
+            Self::Glob(glob) => glob.is_match(value),
+            Self::Regex(regex) => regex.is_match(value),
+        }
+    }
+
+    pub fn matches(&self, node: &Node) -> bool {
+        self.is_match(&node.name)
+            || node.purl.iter().any(|purl| self.is_match(purl))
+            || node.cpe.iter().any(|cpe| self.is_match(cpe))
+    }
+}
+
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct Filter {
+    /// Only keep components matching the pattern, by name, PURL or CPE.
+    ///
+    /// Patterns are globs, unless prefixed with `regex:`. May be used multiple times.
+    #[arg(long, global = true)]
+    pub include: Vec<Pattern>,
+
+    /// Drop components matching the pattern, by name, PURL or CPE.
+    ///
+    /// Patterns are globs, unless prefixed with `regex:`. May be used multiple times.
+    #[arg(long, global = true)]
+    pub exclude: Vec<Pattern>,
+
+    /// Only keep components having a PURL of this type (like `maven` or `npm`). May be used
+    /// multiple times.
+    #[arg(long, global = true)]
+    pub purl_type: Vec<String>,
+
+    /// Only keep components having a CPE of this vendor. May be used multiple times.
+    #[arg(long, global = true)]
+    pub cpe_vendor: Vec<String>,
+
+    /// Only keep components having a CPE of this product. May be used multiple times.
+    #[arg(long, global = true)]
+    pub cpe_product: Vec<String>,
+
+    /// Keep the components connecting included components with the top-level items
+    #[arg(long, global = true, requires = "include")]
+    pub keep_paths: bool,
+
+    /// Only keep SBOMs with this SBOM ID. May be used multiple times.
+    #[arg(long = "sbom", global = true)]
+    pub sbom_ids: Vec<String>,
+
+    /// Only keep SBOMs with this document ID. May be used multiple times.
+    #[arg(long = "document", global = true)]
+    pub document_ids: Vec<String>,
+
+    /// Only keep SBOMs of this product name. May be used multiple times.
+    #[arg(long = "product", global = true)]
+    pub product_names: Vec<String>,
+
+    /// Only keep SBOMs of this product version. May be used multiple times.
+    #[arg(long = "product-version", global = true)]
+    pub product_versions: Vec<String>,
+
+    /// Drop relationships between components of different SBOMs
+    #[arg(long, global = true)]
+    pub drop_cross_sbom: bool,
+}
+
+impl Filter {
+    fn filters_components(&self) -> bool {
+        !self.include.is_empty()
+            || !self.exclude.is_empty()
+            || !self.purl_type.is_empty()
+            || !self.cpe_vendor.is_empty()
+            || !self.cpe_product.is_empty()
+    }
+
+    /// Check if the node matches the include patterns and identifier filters
+    fn includes(&self, node: &Node) -> bool {
+        let cpes = cpes(node);
+
+        (self.include.is_empty() || self.include.iter().any(|pattern| pattern.matches(node)))
+            && accepts(
+                &self.purl_type,
+                purls(node).iter().map(|purl| purl.r#type.as_str()),
+            )
+            && accepts(
+                &self.cpe_vendor,
+                cpes.iter().filter_map(|cpe| cpe.vendor.as_deref()),
+            )
+            && accepts(
+                &self.cpe_product,
+                cpes.iter().filter_map(|cpe| cpe.product.as_deref()),
+            )
+    }
+
+    fn restricts_sboms(&self) -> bool {
+        !self.sbom_ids.is_empty()
+            || !self.document_ids.is_empty()
+            || !self.product_names.is_empty()
+            || !self.product_versions.is_empty()
+    }
+}
+
+/// Check if the value is accepted, an empty list of values accepting everything
+fn accepts<'a>(accepted: &[String], mut values: impl Iterator<Item = &'a str>) -> bool {
+    accepted.is_empty() || values.any(|value| accepted.iter().any(|a| a == value))
+}
+
+impl Graph {
+    /// Apply the filter, removing nodes which don't match.
+    pub fn filter(&mut self, filter: &Filter) {
+        if filter.restricts_sboms() {
+            self.filter_sboms(filter);
+        }
+
+        if filter.drop_cross_sbom {
+            self.retain_relationships(|from, to| from.sbom == to.sbom);
+        }
+
+        if filter.filters_components() {
+            self.filter_components(filter);
+        }
+    }
+
+    fn filter_sboms(&mut self, filter: &Filter) {
+        let sboms = self
+            .nodes
+            .iter()
+            .filter(|(sbom, nodes)| {
+                let document_id = self.sboms.get(*sbom).map(|doc| doc.document_id.as_str());
+
+                accepts(&filter.sbom_ids, std::iter::once(sbom.as_str()))
+                    && accepts(&filter.document_ids, document_id.into_iter())
+                    && accepts(
+                        &filter.product_names,
+                        nodes
+                            .values()
+                            .filter_map(|node| node.product_name.as_deref()),
+                    )
+                    && accepts(
+                        &filter.product_versions,
+                        nodes
+                            .values()
+                            .filter_map(|node| node.product_version.as_deref()),
+                    )
+            })
+            .map(|(sbom, _)| sbom.clone())
+            .collect::<HashSet<_>>();
+
+        self.retain(|key| sboms.contains(&key.sbom));
+    }
+
+    fn filter_components(&mut self, filter: &Filter) {
+        let mut excluded = HashSet::new();
+        let mut keep = HashSet::new();
+
+        for node in self.nodes.values().flat_map(|nodes| nodes.values()) {
+            if filter.exclude.iter().any(|pattern| pattern.matches(node)) {
+                excluded.insert(node.as_key());
+            } else if filter.includes(node) {
+                keep.insert(node.as_key());
+            }
+        }
+
+        if filter.keep_paths {
+            let allowed = |key: &Key| !excluded.contains(key);
+
+            // from the roots down to the matches, and from the matches down to the roots
+            let from_roots = self.reachable(&self.roots, true, None, allowed);
+            let to_roots = self.reachable(&self.roots, false, None, allowed);
+            let from_matches = self.reachable(&keep, true, None, allowed);
+            let to_matches = self.reachable(&keep, false, None, allowed);
+
+            keep.extend(from_roots.intersection(&to_matches).cloned());
+            keep.extend(from_matches.intersection(&to_roots).cloned());
+        }
+
+        self.retain(|key| keep.contains(key));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::test::{graph, keys, node, with, with_purls};
+
+    fn pattern(pattern: &str) -> Pattern {
+        pattern.parse().unwrap()
+    }
+
+    /// app -> lib -> util, app -> test-lib
+    fn sample() -> Graph {
+        graph([with(
+            with_purls("s1", "app", &["pkg:maven/org.ex/app@1"]),
+            [
+                with(
+                    with_purls("s1", "lib", &["pkg:maven/org.ex/lib@1"]),
+                    [with_purls("s1", "util", &["pkg:npm/util@1"])],
+                ),
+                with_purls("s1", "test-lib", &["pkg:maven/org.other/test-lib@1"]),
+            ],
+        )])
+    }
+
+    fn filtered(filter: Filter) -> Vec<String> {
+        let mut graph = sample();
+        graph.filter(&filter);
+        keys(&graph)
+    }
+
+    #[test]
+    fn patterns() {
+        assert!(pattern("pkg:maven/*").is_match("pkg:maven/org.ex/lib@1"));
+        assert!(!pattern("maven").is_match("pkg:maven/org.ex/lib@1"));
+        assert!(pattern("regex:maven").is_match("pkg:maven/org.ex/lib@1"));
+        assert!(!pattern("regex:^maven").is_match("pkg:maven/org.ex/lib@1"));
+        assert!("regex:(".parse::<Pattern>().is_err());
+    }
+
+    #[test]
+    fn include() {
+        let filter = Filter {
+            include: vec![pattern("pkg:maven/org.ex/*")],
+            ..Default::default()
+        };
+        assert_eq!(filtered(filter), vec!["s1--app", "s1--lib"]);
+    }
+
+    #[test]
+    fn exclude() {
+        let filter = Filter {
+            include: vec![pattern("pkg:maven/*")],
+            exclude: vec![pattern("regex:test")],
+            ..Default::default()
+        };
+        assert_eq!(filtered(filter), vec!["s1--app", "s1--lib"]);
+    }
+
+    #[test]
+    fn keep_paths() {
+        let filter = Filter {
+            include: vec![pattern("util")],
+            keep_paths: true,
+            ..Default::default()
+        };
+        assert_eq!(filtered(filter), vec!["s1--app", "s1--lib", "s1--util"]);
+
+        let filter = Filter {
+            include: vec![pattern("util")],
+            exclude: vec![pattern("lib")],
+            keep_paths: true,
+            ..Default::default()
+        };
+        assert_eq!(filtered(filter), vec!["s1--util"]);
+    }
+
+    #[test]
+    fn purl_type() {
+        let filter = Filter {
+            purl_type: vec!["npm".into()],
+            ..Default::default()
+        };
+        assert_eq!(filtered(filter), vec!["s1--util"]);
+    }
+
+    #[test]
+    fn cpe() {
+        let mut a = node("s1", "a");
+        a.cpe
+            .push("cpe:2.3:a:redhat:openssl:3:*:*:*:*:*:*:*".into());
+        let mut b = node("s1", "b");
+        b.cpe.push("cpe:/a:apache:httpd:2".into());
+        let mut graph = graph([a, b, node("s1", "c")]);
+
+        graph.filter(&Filter {
+            cpe_vendor: vec!["redhat".into(), "apache".into()],
+            cpe_product: vec!["httpd".into()],
+            ..Default::default()
+        });
+
+        assert_eq!(keys(&graph), vec!["s1--b"]);
+    }
+
+    /// s1: a -> b, a -> s2--x, s2 (of a product): x -> y
+    fn sboms() -> Graph {
+        let mut product = node("s2", "x");
+        product.product_name = Some("product".into());
+        product.product_version = Some("1.0".into());
+        graph([
+            with(node("s1", "a"), [node("s1", "b"), node("s2", "x")]),
+            with(product, [node("s2", "y")]),
+        ])
+    }
+
+    fn filtered_sboms(filter: Filter) -> Vec<String> {
+        let mut graph = sboms();
+        graph.filter(&filter);
+        keys(&graph)
+    }
+
+    #[test]
+    fn sbom_ids() {
+        let filter = Filter {
+            sbom_ids: vec!["s1".into()],
+            ..Default::default()
+        };
+        assert_eq!(filtered_sboms(filter), vec!["s1--a", "s1--b"]);
+    }
+
+    #[test]
+    fn document_ids() {
+        let filter = Filter {
+            document_ids: vec!["doc-s2".into()],
+            ..Default::default()
+        };
+        assert_eq!(filtered_sboms(filter), vec!["s2--x", "s2--y"]);
+    }
+
+    #[test]
+    fn products() {
+        // only x carries the product, still all of its SBOM is kept
+        let filter = Filter {
+            product_names: vec!["product".into()],
+            ..Default::default()
+        };
+        assert_eq!(filtered_sboms(filter), vec!["s2--x", "s2--y"]);
+
+        let filter = Filter {
+            product_names: vec!["product".into()],
+            product_versions: vec!["1.0".into()],
+            ..Default::default()
+        };
+        assert_eq!(filtered_sboms(filter), vec!["s2--x", "s2--y"]);
+
+        let filter = Filter {
+            product_names: vec!["product".into()],
+            product_versions: vec!["2.0".into()],
+            ..Default::default()
+        };
+        assert!(filtered_sboms(filter).is_empty());
+    }
+
+    #[test]
+    fn drop_cross_sbom() {
+        let mut graph = sboms();
+        graph.filter(&Filter {
+            drop_cross_sbom: true,
+            ..Default::default()
+        });
+        assert_eq!(keys(&graph).len(), 4);
+        assert_eq!(
+            graph
+                .sorted_relationships()
+                .into_iter()
+                .map(|((from, to), _)| (from.to_string(), to.to_string()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("s1--a".into(), "s1--b".into()),
+                ("s2--x".into(), "s2--y".into())
+            ]
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use super::Graph;
+use anyhow::bail;
+
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct Focus {
+    /// Only keep the neighborhood of a component, selected by key (`<sbom>--<node>`), PURL or name
+    #[arg(long, global = true)]
+    pub focus: Option<String>,
+
+    /// Number of hops towards the ancestors of the focused component
+    #[arg(long, global = true, default_value_t = 1, requires = "focus")]
+    pub up: usize,
+
+    /// Number of hops towards the descendants of the focused component
+    #[arg(long, global = true, default_value_t = 1, requires = "focus")]
+    pub down: usize,
+}
+
+impl Graph {
+    /// Apply the focus, removing all nodes outside the neighborhood of the focused component.
+    pub fn focus(&mut self, focus: &Focus) -> anyhow::Result<()> {
+        let Some(selector) = &focus.focus else {
+            return Ok(());
+        };
+
+        let start = self.find(selector);
+        if start.is_empty() {
+            bail!("no component matches '{selector}'");
+        }
+
+        let mut keep = self.reachable(&start, false, Some(focus.up), |_| true);
+        keep.extend(self.reachable(&start, true, Some(focus.down), |_| true));
+
+        self.retain(|key| keep.contains(key));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::test::{graph, keys, node, with};
+
+    /// a -> b -> c -> d -> e
+    fn chain() -> Graph {
+        graph([with(
+            node("s", "a"),
+            [with(
+                node("s", "b"),
+                [with(
+                    node("s", "c"),
+                    [with(node("s", "d"), [node("s", "e")])],
+                )],
+            )],
+        )])
+    }
+
+    fn focused(up: usize, down: usize) -> Vec<String> {
+        let mut graph = chain();
+        graph
+            .focus(&Focus {
+                focus: Some("c".into()),
+                up,
+                down,
+            })
+            .unwrap();
+        keys(&graph)
+    }
+
+    #[test]
+    fn up() {
+        assert_eq!(focused(1, 0), vec!["s--b", "s--c"]);
+        assert_eq!(focused(2, 0), vec!["s--a", "s--b", "s--c"]);
+    }
+
+    #[test]
+    fn down() {
+        assert_eq!(focused(0, 1), vec!["s--c", "s--d"]);
+        assert_eq!(focused(0, 5), vec!["s--c", "s--d", "s--e"]);
+    }
+
+    #[test]
+    fn both() {
+        assert_eq!(focused(1, 1), vec!["s--b", "s--c", "s--d"]);
+    }
+
+    #[test]
+    fn depth_zero() {
+        assert_eq!(focused(0, 0), vec!["s--c"]);
+    }
+
+    #[test]
+    fn no_focus() {
+        let mut graph = chain();
+        graph.focus(&Focus::default()).unwrap();
+        assert_eq!(keys(&graph).len(), 5);
+    }
+
+    #[test]
+    fn unknown() {
+        let mut graph = chain();
+        let err = graph
+            .focus(&Focus {
+                focus: Some("unknown".into()),
+                up: 1,
+                down: 1,
+            })
+            .unwrap_err();
+        assert_eq!(err.to_string(), "no component matches 'unknown'");
+        assert_eq!(keys(&graph).len(), 5);
+    }
+}
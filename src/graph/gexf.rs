@@ -1,3 +1,5 @@
+use super::style::Color;
+use super::{Style, cpes, purls};
 use crate::model::Key;
 use io_adapters::WriteExtension;
 use isx::IsEmpty;
@@ -12,6 +14,32 @@ enum Attr {
     DocumentId,
     Purl,
     Cpe,
+    Source,
+    SbomIds,
+    DocumentIds,
+    PurlType,
+    PurlNamespace,
+    PurlName,
+    PurlVersion,
+    PurlQualifiers,
+    PurlSubpath,
+    CpePart,
+    CpeVendor,
+    CpeProduct,
+    CpeVersion,
+    CpeUpdate,
+    CpeEdition,
+    CpeLanguage,
+    CpeSwEdition,
+    CpeTargetSw,
+    CpeTargetHw,
+    CpeOther,
+}
+
+#[derive(strum::Display)]
+enum EdgeAttr {
+    #[strum(to_string = "RelationshipSource")]
+    Source,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -20,6 +48,8 @@ struct Gexf {
     pub version: String,
     #[serde(rename = "@xmlns")]
     pub xmlns: String,
+    #[serde(rename = "@xmlns:viz")]
+    pub xmlns_viz: String,
 
     pub meta: Meta,
     pub graph: Graph,
@@ -46,7 +76,7 @@ struct Graph {
     pub default_edge_type: String,
 
     #[serde(skip_serializing_if = "IsEmpty::is_empty")]
-    pub attributes: GraphAttributes,
+    pub attributes: Vec<GraphAttributes>,
 
     #[serde(skip_serializing_if = "IsEmpty::is_empty")]
     pub nodes: Nodes,
@@ -144,6 +174,34 @@ struct Node {
     #[serde(skip_serializing_if = "IsEmpty::is_empty")]
     #[serde(rename = "attvalues")]
     pub attributes: Attributes,
+    #[serde(skip_serializing_if = "IsEmpty::is_empty")]
+    #[serde(rename = "viz:color")]
+    pub color: Option<VizColor>,
+    #[serde(skip_serializing_if = "IsEmpty::is_empty")]
+    #[serde(rename = "viz:shape")]
+    pub shape: Option<VizShape>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct VizColor {
+    #[serde(rename = "@r")]
+    pub r: u8,
+    #[serde(rename = "@g")]
+    pub g: u8,
+    #[serde(rename = "@b")]
+    pub b: u8,
+}
+
+impl From<Color> for VizColor {
+    fn from(Color(r, g, b): Color) -> Self {
+        Self { r, g, b }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct VizShape {
+    #[serde(rename = "@value")]
+    pub value: &'static str,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -155,85 +213,318 @@ struct Edge {
     #[serde(rename = "@label")]
     #[serde(skip_serializing_if = "IsEmpty::is_empty")]
     pub label: Option<String>,
+    #[serde(skip_serializing_if = "IsEmpty::is_empty")]
+    #[serde(rename = "attvalues")]
+    pub attributes: Attributes,
+    #[serde(skip_serializing_if = "IsEmpty::is_empty")]
+    #[serde(rename = "viz:color")]
+    pub color: Option<VizColor>,
+    #[serde(skip_serializing_if = "IsEmpty::is_empty")]
+    #[serde(rename = "viz:thickness")]
+    pub thickness: Option<VizThickness>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct VizThickness {
+    #[serde(rename = "@value")]
+    pub value: f32,
 }
 
 impl super::Graph {
-    pub fn render_gexf<W>(self, w: &mut W) -> Result<(), anyhow::Error>
+    pub fn render_gexf<W>(self, style: &Style, w: &mut W) -> Result<(), anyhow::Error>
     where
         W: std::io::Write,
     {
+        let styles = self.styles(style);
+
         let mut nodes = vec![];
         let mut edges = vec![];
 
-        for (sbom, sbom_nodes) in self.nodes {
-            for node in sbom_nodes.values() {
+        for (sbom, sbom_nodes) in self.sorted_nodes() {
+            for node in sbom_nodes {
                 let key = Key {
                     sbom: sbom.clone(),
                     node: node.node_id.clone(),
                 };
+                let purls = purls(node);
+                let cpes = cpes(node);
+                let node_style = styles.node(node);
                 nodes.push(Node {
                     id: key.to_string(),
                     pid: None,
-                    label: Some(node.name.clone()),
+                    label: Some(match &node_style.label {
+                        Some(label) => label.node(node),
+                        None => node.name.clone(),
+                    }),
                     nodes: Default::default(),
                     attributes: Attributes::new()
                         .add(Attr::SbomId, sbom.clone())
                         .add(Attr::DocumentId, node.document_id.clone())
                         .extend(Attr::Cpe, &node.cpe)
-                        .extend(Attr::Purl, &node.purl),
+                        .extend(Attr::Purl, &node.purl)
+                        .extend(Attr::Source, self.node_sources(&key))
+                        .extend(Attr::SbomIds, self.node_sboms(&key))
+                        .extend(Attr::DocumentIds, self.node_documents(&key))
+                        .extend(Attr::PurlType, purls.iter().map(|p| &p.r#type))
+                        .extend(
+                            Attr::PurlNamespace,
+                            purls.iter().filter_map(|p| p.namespace.as_ref()),
+                        )
+                        .extend(Attr::PurlName, purls.iter().map(|p| &p.name))
+                        .extend(
+                            Attr::PurlVersion,
+                            purls.iter().filter_map(|p| p.version.as_ref()),
+                        )
+                        .extend(
+                            Attr::PurlQualifiers,
+                            purls
+                                .iter()
+                                .filter(|p| !p.qualifiers.is_empty())
+                                .map(|p| p.qualifiers_string()),
+                        )
+                        .extend(
+                            Attr::PurlSubpath,
+                            purls.iter().filter_map(|p| p.subpath.as_ref()),
+                        )
+                        .extend(Attr::CpePart, cpes.iter().filter_map(|c| c.part.as_ref()))
+                        .extend(
+                            Attr::CpeVendor,
+                            cpes.iter().filter_map(|c| c.vendor.as_ref()),
+                        )
+                        .extend(
+                            Attr::CpeProduct,
+                            cpes.iter().filter_map(|c| c.product.as_ref()),
+                        )
+                        .extend(
+                            Attr::CpeVersion,
+                            cpes.iter().filter_map(|c| c.version.as_ref()),
+                        )
+                        .extend(
+                            Attr::CpeUpdate,
+                            cpes.iter().filter_map(|c| c.update.as_ref()),
+                        )
+                        .extend(
+                            Attr::CpeEdition,
+                            cpes.iter().filter_map(|c| c.edition.as_ref()),
+                        )
+                        .extend(
+                            Attr::CpeLanguage,
+                            cpes.iter().filter_map(|c| c.language.as_ref()),
+                        )
+                        .extend(
+                            Attr::CpeSwEdition,
+                            cpes.iter().filter_map(|c| c.sw_edition.as_ref()),
+                        )
+                        .extend(
+                            Attr::CpeTargetSw,
+                            cpes.iter().filter_map(|c| c.target_sw.as_ref()),
+                        )
+                        .extend(
+                            Attr::CpeTargetHw,
+                            cpes.iter().filter_map(|c| c.target_hw.as_ref()),
+                        )
+                        .extend(Attr::CpeOther, cpes.iter().filter_map(|c| c.other.as_ref())),
+                    color: node_style.color.map(Into::into),
+                    shape: node_style.shape.map(|shape| VizShape {
+                        value: shape.gexf(),
+                    }),
                 })
             }
         }
 
-        for ((from, to), rel) in self.relationships {
+        for ((from, to), rel) in self.sorted_relationships() {
+            let edge_style = styles.edge(from, rel);
             edges.push(Edge {
                 source: from.to_string(),
                 target: to.to_string(),
-                label: Some(rel),
+                label: Some(match &edge_style.label {
+                    Some(label) => label.edge(rel),
+                    None => rel.clone(),
+                }),
+                attributes: Attributes::new()
+                    .extend(EdgeAttr::Source, self.relationship_sources(from, to)),
+                color: edge_style.color.map(Into::into),
+                thickness: edge_style.pen_width.map(|value| VizThickness { value }),
             })
         }
 
         let gexf = Gexf {
             version: "1.3".into(),
             xmlns: "http://gexf.net/1.3".into(),
+            xmlns_viz: "http://gexf.net/1.3/viz".into(),
             meta: Meta {
                 creator: "treeify".into(),
-                description: None,
+                description: styles.has_legend().then(|| {
+                    let legend = [styles.node_legend(), styles.edge_legend()].concat();
+                    format!("Legend: {}", legend.join("; "))
+                }),
                 last_modified_date: OffsetDateTime::now_utc(),
             },
             graph: Graph {
                 mode: Some("static".into()),
                 default_edge_type: "directed".to_string(),
-                attributes: GraphAttributes {
-                    class: "node".into(),
-                    mode: None,
-                    attributes: vec![
-                        GraphAttribute {
-                            id: Attr::SbomId.to_string(),
-                            title: "SBOM ID".to_string(),
-                            r#type: "string".to_string(),
-                            default: None,
-                        },
-                        GraphAttribute {
-                            id: Attr::DocumentId.to_string(),
-                            title: "Document ID".to_string(),
-                            r#type: "string".to_string(),
-                            default: None,
-                        },
-                        GraphAttribute {
-                            id: Attr::Cpe.to_string(),
-                            title: "CPE".to_string(),
-                            r#type: "liststring".to_string(),
-                            default: None,
-                        },
-                        GraphAttribute {
-                            id: Attr::Purl.to_string(),
-                            title: "PURL".to_string(),
+                attributes: vec![
+                    GraphAttributes {
+                        class: "node".into(),
+                        mode: None,
+                        attributes: vec![
+                            GraphAttribute {
+                                id: Attr::SbomId.to_string(),
+                                title: "SBOM ID".to_string(),
+                                r#type: "string".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::DocumentId.to_string(),
+                                title: "Document ID".to_string(),
+                                r#type: "string".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::Cpe.to_string(),
+                                title: "CPE".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::Purl.to_string(),
+                                title: "PURL".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::Source.to_string(),
+                                title: "Source".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::SbomIds.to_string(),
+                                title: "SBOM IDs".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::DocumentIds.to_string(),
+                                title: "Document IDs".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::PurlType.to_string(),
+                                title: "PURL type".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::PurlNamespace.to_string(),
+                                title: "PURL namespace".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::PurlName.to_string(),
+                                title: "PURL name".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::PurlVersion.to_string(),
+                                title: "PURL version".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::PurlQualifiers.to_string(),
+                                title: "PURL qualifiers".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::PurlSubpath.to_string(),
+                                title: "PURL subpath".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::CpePart.to_string(),
+                                title: "CPE part".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::CpeVendor.to_string(),
+                                title: "CPE vendor".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::CpeProduct.to_string(),
+                                title: "CPE product".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::CpeVersion.to_string(),
+                                title: "CPE version".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::CpeUpdate.to_string(),
+                                title: "CPE update".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::CpeEdition.to_string(),
+                                title: "CPE edition".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::CpeLanguage.to_string(),
+                                title: "CPE language".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::CpeSwEdition.to_string(),
+                                title: "CPE software edition".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::CpeTargetSw.to_string(),
+                                title: "CPE target software".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::CpeTargetHw.to_string(),
+                                title: "CPE target hardware".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                            GraphAttribute {
+                                id: Attr::CpeOther.to_string(),
+                                title: "CPE other".to_string(),
+                                r#type: "liststring".to_string(),
+                                default: None,
+                            },
+                        ],
+                    },
+                    GraphAttributes {
+                        class: "edge".into(),
+                        mode: None,
+                        attributes: vec![GraphAttribute {
+                            id: EdgeAttr::Source.to_string(),
+                            title: "Source".to_string(),
                             r#type: "liststring".to_string(),
                             default: None,
-                        },
-                    ],
-                },
+                        }],
+                    },
+                ],
                 nodes: Nodes { node: nodes },
                 edges: Edges { edge: edges },
             },
@@ -244,3 +535,48 @@ impl super::Graph {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::graph::Style;
+    use crate::graph::style::StyleBy;
+    use crate::graph::test::{render, styled, xml_node};
+    use regex::Regex;
+
+    #[test]
+    fn stable_order() {
+        let style = Style {
+            style_by: Some(StyleBy::Sbom),
+            color_relationships: true,
+            ..Default::default()
+        };
+        // only the modification date may change
+        let date = Regex::new(r#"lastmodifieddate="[^"]*""#).unwrap();
+        let render = || {
+            let gexf = render(|graph, w| graph.render_gexf(&style, w));
+            date.replace(&gexf, "").into_owned()
+        };
+        assert_eq!(render(), render());
+    }
+
+    #[test]
+    fn styled_node() {
+        let (graph, style) = styled();
+        let mut output = vec![];
+        graph.render_gexf(&style, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(
+            xml_node(&output, "s1--a")
+                .ends_with(r#"<viz:color r="78" g="121" b="167"/><viz:shape value="square"/>"#)
+        );
+        assert!(
+            xml_node(&output, "s1--b")
+                .ends_with(r#"<viz:color r="242" g="142" b="43"/><viz:shape value="disc"/>"#)
+        );
+        assert!(output.contains(r#"<viz:color r="78" g="121" b="167"/></edge>"#));
+        assert!(output.contains(
+            "<description>Legend: maven: #4e79a7 box; unknown: #f28e2b ellipse; dependency_of: #4e79a7</description>"
+        ));
+    }
+}
@@ -1,3 +1,5 @@
+use super::style::{EdgeStyle, NodeStyle, Shape};
+use super::{Style, cpes, purls};
 use crate::model::Key;
 use io_adapters::WriteExtension;
 use isx::IsEmpty;
@@ -11,21 +13,61 @@ enum NodeAttr {
     DocumentId,
     Purl,
     Cpe,
+    Source,
+    SbomIds,
+    DocumentIds,
+    PurlType,
+    PurlNamespace,
+    PurlName,
+    PurlVersion,
+    PurlQualifiers,
+    PurlSubpath,
+    CpePart,
+    CpeVendor,
+    CpeProduct,
+    CpeVersion,
+    CpeUpdate,
+    CpeEdition,
+    CpeLanguage,
+    CpeSwEdition,
+    CpeTargetSw,
+    CpeTargetHw,
+    CpeOther,
+}
+
+#[derive(strum::Display)]
+enum GraphAttr {
+    NodeLegend,
+    EdgeLegend,
 }
 
 #[derive(strum::Display)]
 enum EdgeAttr {
     Relationship,
+    #[strum(to_string = "RelationshipSource")]
+    Source,
+}
+
+/// Keys of the yEd graphics
+#[derive(strum::Display)]
+enum Graphics {
+    NodeGraphics,
+    EdgeGraphics,
 }
 
 #[derive(Serialize, Debug, Clone)]
 struct GraphML {
     #[serde(rename = "@xmlns")]
     pub xmlns: String,
+    #[serde(rename = "@xmlns:y")]
+    pub xmlns_y: String,
 
     #[serde(skip_serializing_if = "IsEmpty::is_empty")]
     #[serde(rename = "key")]
     pub keys: Vec<GraphAttribute>,
+    #[serde(skip_serializing_if = "IsEmpty::is_empty")]
+    #[serde(rename = "key")]
+    pub graphics_keys: Vec<GraphicsKey>,
 
     pub graph: Graph,
 }
@@ -37,6 +79,10 @@ struct Graph {
     #[serde(rename = "@edgedefault")]
     pub edge_default: String,
 
+    #[serde(skip_serializing_if = "IsEmpty::is_empty")]
+    #[serde(rename = "data")]
+    pub data: Vec<Attribute>,
+
     #[serde(skip_serializing_if = "IsEmpty::is_empty")]
     #[serde(rename = "node")]
     pub nodes: Vec<Node>,
@@ -59,6 +105,17 @@ struct GraphAttribute {
     pub default: Option<String>,
 }
 
+/// A key for yEd graphics
+#[derive(Serialize, Debug, Clone)]
+struct GraphicsKey {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "@for")]
+    pub r#for: String,
+    #[serde(rename = "@yfiles.type")]
+    pub yfiles_type: String,
+}
+
 #[derive(Serialize, Debug, Clone, Default, IsEmpty)]
 struct Data(pub Vec<Attribute>);
 
@@ -70,11 +127,22 @@ impl Data {
     pub fn add(mut self, key: impl Display, value: impl Display) -> Self {
         self.0.push(Attribute {
             key: key.to_string(),
-            value: value.to_string(),
+            value: Value::Text(value.to_string()),
         });
         self
     }
 
+    /// Add yEd graphics, if present
+    pub fn graphics(mut self, key: impl Display, value: Option<Value>) -> Self {
+        if let Some(value) = value {
+            self.0.push(Attribute {
+                key: key.to_string(),
+                value,
+            });
+        }
+        self
+    }
+
     pub fn extend(
         mut self,
         key: impl Display,
@@ -83,7 +151,7 @@ impl Data {
         let key = key.to_string();
         self.0.extend(values.into_iter().map(|value| Attribute {
             key: key.clone(),
-            value: value.to_string(),
+            value: Value::Text(value.to_string()),
         }));
         self
     }
@@ -97,8 +165,72 @@ impl Data {
 pub struct Attribute {
     #[serde(rename = "@key")]
     pub key: String,
+    #[serde(rename = "$value")]
+    pub value: Value,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub enum Value {
     #[serde(rename = "$text")]
-    pub value: String,
+    Text(String),
+    #[serde(rename = "y:ShapeNode")]
+    ShapeNode(ShapeNode),
+    #[serde(rename = "y:PolyLineEdge")]
+    PolyLineEdge(PolyLineEdge),
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ShapeNode {
+    #[serde(rename = "y:Fill")]
+    pub fill: Fill,
+    #[serde(rename = "y:BorderStyle")]
+    pub border_style: LineStyle,
+    #[serde(rename = "y:NodeLabel")]
+    pub label: String,
+    #[serde(rename = "y:Shape")]
+    pub shape: YShape,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Fill {
+    #[serde(rename = "@color")]
+    pub color: String,
+    #[serde(rename = "@transparent")]
+    pub transparent: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct YShape {
+    #[serde(rename = "@type")]
+    pub r#type: &'static str,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PolyLineEdge {
+    #[serde(rename = "y:LineStyle")]
+    pub line_style: LineStyle,
+    #[serde(rename = "y:Arrows")]
+    pub arrows: Arrows,
+    #[serde(rename = "y:EdgeLabel")]
+    pub label: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct LineStyle {
+    #[serde(rename = "@color")]
+    pub color: String,
+    #[serde(rename = "@type")]
+    pub r#type: &'static str,
+    #[serde(rename = "@width")]
+    pub width: f32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Arrows {
+    #[serde(rename = "@source")]
+    pub source: &'static str,
+    #[serde(rename = "@target")]
+    pub target: &'static str,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -126,23 +258,86 @@ struct Edge {
     pub data: Vec<Attribute>,
 }
 
+/// The yEd graphics of a node, if it has any style
+fn shape_node(node: &crate::model::Node, style: NodeStyle) -> Option<Value> {
+    if style.color.is_none()
+        && style.shape.is_none()
+        && style.pen_width.is_none()
+        && style.label.is_none()
+    {
+        return None;
+    }
+
+    Some(Value::ShapeNode(ShapeNode {
+        fill: Fill {
+            color: style
+                .color
+                .map(|color| color.to_string())
+                .unwrap_or("#ffffff".into()),
+            transparent: false,
+        },
+        border_style: LineStyle {
+            color: "#000000".into(),
+            r#type: "line",
+            width: style.pen_width.unwrap_or(1.0),
+        },
+        label: match &style.label {
+            Some(label) => label.node(node),
+            None => node.name.clone(),
+        },
+        shape: YShape {
+            r#type: style.shape.unwrap_or(Shape::Box).yed(),
+        },
+    }))
+}
+
+/// The yEd graphics of an edge, if it has any style
+fn poly_line_edge(rel: &str, style: EdgeStyle) -> Option<Value> {
+    if style.color.is_none() && style.pen_width.is_none() && style.label.is_none() {
+        return None;
+    }
+
+    Some(Value::PolyLineEdge(PolyLineEdge {
+        line_style: LineStyle {
+            color: style
+                .color
+                .map(|color| color.to_string())
+                .unwrap_or("#000000".into()),
+            r#type: "line",
+            width: style.pen_width.unwrap_or(1.0),
+        },
+        arrows: Arrows {
+            source: "none",
+            target: "standard",
+        },
+        label: match &style.label {
+            Some(label) => label.edge(rel),
+            None => rel.to_string(),
+        },
+    }))
+}
+
 impl super::Graph {
-    pub fn render_graphml<W>(self, w: &mut W) -> Result<(), anyhow::Error>
+    pub fn render_graphml<W>(self, style: &Style, w: &mut W) -> Result<(), anyhow::Error>
     where
         W: std::io::Write,
     {
+        let styles = self.styles(style);
+
         let mut nodes = vec![];
         let mut edges = vec![];
 
-        for (sbom, sbom_nodes) in self.nodes {
-            let sbom_doc = self.sboms.get(&sbom).expect("sbom doc not found");
+        for (sbom, sbom_nodes) in self.sorted_nodes() {
+            let sbom_doc = self.sboms.get(sbom).expect("sbom doc not found");
 
             let mut children = vec![];
-            for node in sbom_nodes.values() {
+            for node in sbom_nodes {
                 let key = Key {
                     sbom: sbom.clone(),
                     node: node.node_id.clone(),
                 };
+                let purls = purls(node);
+                let cpes = cpes(node);
                 children.push(Node {
                     id: key.to_string(),
                     graph: Default::default(),
@@ -152,6 +347,75 @@ impl super::Graph {
                         .add(NodeAttr::DocumentId, node.document_id.clone())
                         .extend(NodeAttr::Cpe, &node.cpe)
                         .extend(NodeAttr::Purl, &node.purl)
+                        .extend(NodeAttr::Source, self.node_sources(&key))
+                        .extend(NodeAttr::SbomIds, self.node_sboms(&key))
+                        .extend(NodeAttr::DocumentIds, self.node_documents(&key))
+                        .extend(NodeAttr::PurlType, purls.iter().map(|p| &p.r#type))
+                        .extend(
+                            NodeAttr::PurlNamespace,
+                            purls.iter().filter_map(|p| p.namespace.as_ref()),
+                        )
+                        .extend(NodeAttr::PurlName, purls.iter().map(|p| &p.name))
+                        .extend(
+                            NodeAttr::PurlVersion,
+                            purls.iter().filter_map(|p| p.version.as_ref()),
+                        )
+                        .extend(
+                            NodeAttr::PurlQualifiers,
+                            purls
+                                .iter()
+                                .filter(|p| !p.qualifiers.is_empty())
+                                .map(|p| p.qualifiers_string()),
+                        )
+                        .extend(
+                            NodeAttr::PurlSubpath,
+                            purls.iter().filter_map(|p| p.subpath.as_ref()),
+                        )
+                        .extend(
+                            NodeAttr::CpePart,
+                            cpes.iter().filter_map(|c| c.part.as_ref()),
+                        )
+                        .extend(
+                            NodeAttr::CpeVendor,
+                            cpes.iter().filter_map(|c| c.vendor.as_ref()),
+                        )
+                        .extend(
+                            NodeAttr::CpeProduct,
+                            cpes.iter().filter_map(|c| c.product.as_ref()),
+                        )
+                        .extend(
+                            NodeAttr::CpeVersion,
+                            cpes.iter().filter_map(|c| c.version.as_ref()),
+                        )
+                        .extend(
+                            NodeAttr::CpeUpdate,
+                            cpes.iter().filter_map(|c| c.update.as_ref()),
+                        )
+                        .extend(
+                            NodeAttr::CpeEdition,
+                            cpes.iter().filter_map(|c| c.edition.as_ref()),
+                        )
+                        .extend(
+                            NodeAttr::CpeLanguage,
+                            cpes.iter().filter_map(|c| c.language.as_ref()),
+                        )
+                        .extend(
+                            NodeAttr::CpeSwEdition,
+                            cpes.iter().filter_map(|c| c.sw_edition.as_ref()),
+                        )
+                        .extend(
+                            NodeAttr::CpeTargetSw,
+                            cpes.iter().filter_map(|c| c.target_sw.as_ref()),
+                        )
+                        .extend(
+                            NodeAttr::CpeTargetHw,
+                            cpes.iter().filter_map(|c| c.target_hw.as_ref()),
+                        )
+                        .extend(
+                            NodeAttr::CpeOther,
+                            cpes.iter().filter_map(|c| c.other.as_ref()),
+                        )
+                        .graphics(Graphics::NodeGraphics, shape_node(node, styles.node(node)))
                         .into_vec(),
                 })
             }
@@ -164,23 +428,32 @@ impl super::Graph {
                 graph: Some(Graph {
                     id: sbom.clone(),
                     edge_default: "directed".to_string(),
+                    data: vec![],
                     nodes: children,
                     edges: vec![],
                 }),
             });
         }
 
-        for ((from, to), rel) in self.relationships {
+        for ((from, to), rel) in self.sorted_relationships() {
             edges.push(Edge {
                 id: None,
                 source: from.to_string(),
                 target: to.to_string(),
-                data: Data::new().add(EdgeAttr::Relationship, rel).into_vec(),
+                data: Data::new()
+                    .add(EdgeAttr::Relationship, rel)
+                    .extend(EdgeAttr::Source, self.relationship_sources(from, to))
+                    .graphics(
+                        Graphics::EdgeGraphics,
+                        poly_line_edge(rel, styles.edge(from, rel)),
+                    )
+                    .into_vec(),
             })
         }
 
         let gml = GraphML {
             xmlns: "http://graphml.graphdrawing.org/xmlns".into(),
+            xmlns_y: "http://www.yworks.com/xml/graphml".into(),
             keys: vec![
                 GraphAttribute {
                     id: NodeAttr::Name.to_string(),
@@ -217,6 +490,160 @@ impl super::Graph {
                     r#type: "string".to_string(),
                     default: None,
                 },
+                GraphAttribute {
+                    id: NodeAttr::Source.to_string(),
+                    r#for: "node".to_string(),
+                    title: "Source".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::SbomIds.to_string(),
+                    r#for: "node".to_string(),
+                    title: "SBOM IDs".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::DocumentIds.to_string(),
+                    r#for: "node".to_string(),
+                    title: "Document IDs".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::PurlType.to_string(),
+                    r#for: "node".to_string(),
+                    title: "PURL type".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::PurlNamespace.to_string(),
+                    r#for: "node".to_string(),
+                    title: "PURL namespace".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::PurlName.to_string(),
+                    r#for: "node".to_string(),
+                    title: "PURL name".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::PurlVersion.to_string(),
+                    r#for: "node".to_string(),
+                    title: "PURL version".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::PurlQualifiers.to_string(),
+                    r#for: "node".to_string(),
+                    title: "PURL qualifiers".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::PurlSubpath.to_string(),
+                    r#for: "node".to_string(),
+                    title: "PURL subpath".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::CpePart.to_string(),
+                    r#for: "node".to_string(),
+                    title: "CPE part".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::CpeVendor.to_string(),
+                    r#for: "node".to_string(),
+                    title: "CPE vendor".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::CpeProduct.to_string(),
+                    r#for: "node".to_string(),
+                    title: "CPE product".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::CpeVersion.to_string(),
+                    r#for: "node".to_string(),
+                    title: "CPE version".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::CpeUpdate.to_string(),
+                    r#for: "node".to_string(),
+                    title: "CPE update".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::CpeEdition.to_string(),
+                    r#for: "node".to_string(),
+                    title: "CPE edition".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::CpeLanguage.to_string(),
+                    r#for: "node".to_string(),
+                    title: "CPE language".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::CpeSwEdition.to_string(),
+                    r#for: "node".to_string(),
+                    title: "CPE software edition".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::CpeTargetSw.to_string(),
+                    r#for: "node".to_string(),
+                    title: "CPE target software".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::CpeTargetHw.to_string(),
+                    r#for: "node".to_string(),
+                    title: "CPE target hardware".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: NodeAttr::CpeOther.to_string(),
+                    r#for: "node".to_string(),
+                    title: "CPE other".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: GraphAttr::NodeLegend.to_string(),
+                    r#for: "graph".to_string(),
+                    title: "Component legend".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+                GraphAttribute {
+                    id: GraphAttr::EdgeLegend.to_string(),
+                    r#for: "graph".to_string(),
+                    title: "Relationship legend".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
                 GraphAttribute {
                     id: EdgeAttr::Relationship.to_string(),
                     r#for: "edge".to_string(),
@@ -224,10 +651,33 @@ impl super::Graph {
                     r#type: "string".to_string(),
                     default: None,
                 },
+                GraphAttribute {
+                    id: EdgeAttr::Source.to_string(),
+                    r#for: "edge".to_string(),
+                    title: "Source".to_string(),
+                    r#type: "string".to_string(),
+                    default: None,
+                },
+            ],
+            graphics_keys: vec![
+                GraphicsKey {
+                    id: Graphics::NodeGraphics.to_string(),
+                    r#for: "node".to_string(),
+                    yfiles_type: "nodegraphics".to_string(),
+                },
+                GraphicsKey {
+                    id: Graphics::EdgeGraphics.to_string(),
+                    r#for: "edge".to_string(),
+                    yfiles_type: "edgegraphics".to_string(),
+                },
             ],
             graph: Graph {
                 id: "ID".into(),
                 edge_default: "directed".to_string(),
+                data: Data::new()
+                    .extend(GraphAttr::NodeLegend, styles.node_legend())
+                    .extend(GraphAttr::EdgeLegend, styles.edge_legend())
+                    .into_vec(),
                 nodes,
                 edges,
             },
@@ -238,3 +688,41 @@ impl super::Graph {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::graph::Style;
+    use crate::graph::style::StyleBy;
+    use crate::graph::test::{render, styled, xml_node};
+
+    #[test]
+    fn stable_order() {
+        let style = Style {
+            style_by: Some(StyleBy::Sbom),
+            color_relationships: true,
+            ..Default::default()
+        };
+        let render = || render(|graph, w| graph.render_graphml(&style, w));
+        assert_eq!(render(), render());
+    }
+
+    #[test]
+    fn styled_node() {
+        let (graph, style) = styled();
+        let mut output = vec![];
+        graph.render_graphml(&style, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let a = xml_node(&output, "s1--a");
+        assert!(a.contains(r##"<y:Fill color="#4e79a7" transparent="false"/>"##));
+        assert!(a.contains(r#"<y:Shape type="rectangle"/>"#));
+        let b = xml_node(&output, "s1--b");
+        assert!(b.contains(r##"<y:Fill color="#f28e2b" transparent="false"/>"##));
+        assert!(b.contains(r#"<y:Shape type="ellipse"/>"#));
+        assert!(output.contains(r##"<y:LineStyle color="#4e79a7" type="line" width="1"/>"##));
+        assert!(output.contains(
+            r#"<data key="NodeLegend">maven: #4e79a7 box</data><data key="NodeLegend">unknown: #f28e2b ellipse</data>"#
+        ));
+        assert!(output.contains(r#"<data key="EdgeLegend">dependency_of: #4e79a7</data>"#));
+    }
+}
@@ -1,6 +1,6 @@
-use super::Graph;
+use super::{Graph, Style, cpes};
 use crate::model::Key;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 
 impl Key {
@@ -11,7 +11,7 @@ impl Key {
 
 #[derive(Debug, Default)]
 struct Attributes {
-    data: HashMap<String, String>,
+    data: BTreeMap<String, String>,
     subgraph: bool,
 }
 
@@ -31,6 +31,19 @@ impl Attributes {
         self.data.insert(key.into(), value.into());
         self
     }
+
+    /// Add a list of values, joined into a single value, unless there are none
+    pub fn add_all<'a>(
+        self,
+        key: impl Into<String>,
+        values: impl IntoIterator<Item = &'a String>,
+    ) -> Self {
+        let value = join(values);
+        match value.is_empty() {
+            true => self,
+            false => self.add(key, value),
+        }
+    }
 }
 
 impl Display for Attributes {
@@ -62,11 +75,21 @@ fn encode(s: impl AsRef<str>) -> String {
         .to_string()
 }
 
+fn join<'a>(values: impl IntoIterator<Item = &'a String>) -> String {
+    values
+        .into_iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 impl Graph {
-    pub fn render_grapviz<W>(self, w: &mut W) -> Result<(), std::io::Error>
+    pub fn render_grapviz<W>(self, style: &Style, w: &mut W) -> Result<(), std::io::Error>
     where
         W: std::io::Write,
     {
+        let styles = self.styles(style);
+
         writeln!(
             w,
             r#"
@@ -81,7 +104,7 @@ digraph {{
 "#
         )?;
 
-        for (sbom, nodes) in &self.nodes {
+        for (sbom, nodes) in self.sorted_nodes() {
             let sbom_doc = self.sboms.get(sbom).expect("SBOM not found");
 
             writeln!(
@@ -100,15 +123,67 @@ subgraph "{id}" {{
                 ),
             )?;
 
-            for node in nodes.values() {
+            for node in nodes {
+                let cpes = cpes(node);
+                let mut attrs = Attributes::new()
+                    .add("label", &node.node_id)
+                    .add("shape", "box")
+                    .add_all("sources", self.node_sources(&node.as_key()))
+                    .add_all("sbom_ids", &self.node_sboms(&node.as_key()))
+                    .add_all("document_ids", self.node_documents(&node.as_key()))
+                    .add_all("cpe_part", cpes.iter().filter_map(|c| c.part.as_ref()))
+                    .add_all("cpe_vendor", cpes.iter().filter_map(|c| c.vendor.as_ref()))
+                    .add_all(
+                        "cpe_product",
+                        cpes.iter().filter_map(|c| c.product.as_ref()),
+                    )
+                    .add_all(
+                        "cpe_version",
+                        cpes.iter().filter_map(|c| c.version.as_ref()),
+                    )
+                    .add_all("cpe_update", cpes.iter().filter_map(|c| c.update.as_ref()))
+                    .add_all(
+                        "cpe_edition",
+                        cpes.iter().filter_map(|c| c.edition.as_ref()),
+                    )
+                    .add_all(
+                        "cpe_language",
+                        cpes.iter().filter_map(|c| c.language.as_ref()),
+                    )
+                    .add_all(
+                        "cpe_sw_edition",
+                        cpes.iter().filter_map(|c| c.sw_edition.as_ref()),
+                    )
+                    .add_all(
+                        "cpe_target_sw",
+                        cpes.iter().filter_map(|c| c.target_sw.as_ref()),
+                    )
+                    .add_all(
+                        "cpe_target_hw",
+                        cpes.iter().filter_map(|c| c.target_hw.as_ref()),
+                    )
+                    .add_all("cpe_other", cpes.iter().filter_map(|c| c.other.as_ref()));
+                let style = styles.node(node);
+                if let Some(color) = style.color {
+                    attrs = attrs
+                        .add("style", "filled")
+                        .add("fillcolor", color.to_string());
+                }
+                if let Some(shape) = style.shape {
+                    attrs = attrs.add("shape", shape.dot());
+                }
+                if let Some(pen_width) = style.pen_width {
+                    attrs = attrs.add("penwidth", pen_width.to_string());
+                }
+                if let Some(label) = &style.label {
+                    attrs = attrs.add("label", label.node(node));
+                }
+
                 writeln!(
                     w,
                     r#"  "{sbom}--{node}" {attrs}"#,
                     sbom = encode(&node.sbom_id),
                     node = encode(&node.node_id),
-                    attrs = Attributes::new()
-                        .add("label", node.node_id.clone())
-                        .add("shape", "box")
                 )?;
             }
 
@@ -120,18 +195,75 @@ subgraph "{id}" {{
             )?;
         }
 
-        for ((from, to), rel) in self.relationships {
+        for ((from, to), rel) in self.sorted_relationships() {
+            let mut attrs = Attributes::new()
+                .add(
+                    "group",
+                    format!("{left}-{right}", left = from.sbom, right = to.sbom),
+                )
+                .add("label", rel)
+                .add_all("sources", self.relationship_sources(from, to));
+            let style = styles.edge(from, rel);
+            if let Some(color) = style.color {
+                attrs = attrs
+                    .add("color", color.to_string())
+                    .add("fontcolor", color.to_string());
+            }
+            if let Some(pen_width) = style.pen_width {
+                attrs = attrs.add("penwidth", pen_width.to_string());
+            }
+            if let Some(label) = &style.label {
+                attrs = attrs.add("label", label.edge(rel));
+            }
+
             writeln!(
                 w,
                 r#"  "{left}" -> "{right}" {attrs}"#,
                 left = from.encode(),
                 right = to.encode(),
-                attrs = Attributes::new()
-                    .add(
-                        "group",
-                        format!("{left}-{right}", left = from.sbom, right = to.sbom)
-                    )
-                    .add("label", rel)
+            )?;
+        }
+
+        if styles.has_legend() {
+            writeln!(
+                w,
+                r#"
+subgraph "legend" {{
+{attrs}
+"#,
+                attrs = Attributes::subgraph()
+                    .add("cluster", "true")
+                    .add("label", "Legend"),
+            )?;
+
+            for (i, (category, (color, shape))) in styles.categories.iter().enumerate() {
+                writeln!(
+                    w,
+                    r#"  "legend--{i}" {attrs}"#,
+                    attrs = Attributes::new()
+                        .add("label", category)
+                        .add("shape", shape.dot())
+                        .add("style", "filled")
+                        .add("fillcolor", color.to_string())
+                )?;
+            }
+
+            for (i, (rel, color)) in styles.relationships.iter().enumerate() {
+                writeln!(
+                    w,
+                    r#"  "legend-relationship--{i}" {attrs}"#,
+                    attrs = Attributes::new()
+                        .add("label", rel)
+                        .add("shape", "plaintext")
+                        .add("fontcolor", color.to_string())
+                )?;
+            }
+
+            writeln!(
+                w,
+                r#"
+}}
+"#
             )?;
         }
 
@@ -145,3 +277,63 @@ subgraph "{id}" {{
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::graph::Style;
+    use crate::graph::style::StyleBy;
+    use crate::graph::test::{graph, node, render, styled};
+
+    #[test]
+    fn stable_order() {
+        let style = Style {
+            style_by: Some(StyleBy::Sbom),
+            color_relationships: true,
+            ..Default::default()
+        };
+        let render = || render(|graph, w| Ok(graph.render_grapviz(&style, w)?));
+        assert_eq!(render(), render());
+    }
+
+    #[test]
+    fn skip_empty_attributes() {
+        let mut a = node("s1", "a");
+        a.cpe
+            .push("cpe:2.3:a:redhat:openssl:3.0:*:*:*:*:*:*:*".into());
+        let mut output = vec![];
+        graph([a, node("s1", "b")])
+            .render_grapviz(&Style::default(), &mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let line = |id: &str| {
+            output
+                .lines()
+                .find(|line| line.trim_start().starts_with(&format!(r#""s1--{id}""#)))
+                .unwrap()
+        };
+        assert!(line("a").contains(r#""cpe_vendor" = "redhat""#));
+        assert!(line("a").contains(r#""cpe_version" = "3.0""#));
+        assert!(!line("a").contains("cpe_update"));
+        assert!(!line("b").contains("cpe_"));
+        assert!(line("b").contains(r#""sources" = "test.json""#));
+    }
+
+    #[test]
+    fn styled_node() {
+        let (graph, style) = styled();
+        let mut output = vec![];
+        graph.render_grapviz(&style, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains(r##""s1--a" ["document_ids" = "doc-s1" "fillcolor" = "#4e79a7" "label" = "a" "sbom_ids" = "s1" "shape" = "box" "sources" = "test.json" "style" = "filled" ]"##));
+        assert!(output.contains(
+            r##""fillcolor" = "#f28e2b" "label" = "b" "sbom_ids" = "s1" "shape" = "ellipse""##
+        ));
+        assert!(
+            output.contains(r##""s1--a" -> "s1--b" ["color" = "#4e79a7" "fontcolor" = "#4e79a7""##)
+        );
+        assert!(output.contains(r##""legend--0" ["fillcolor" = "#4e79a7" "label" = "maven" "shape" = "box" "style" = "filled" ]"##));
+        assert!(output.contains(r##""legend-relationship--0" ["fontcolor" = "#4e79a7" "label" = "dependency_of" "shape" = "plaintext" ]"##));
+    }
+}
@@ -0,0 +1,153 @@
+use super::cpes;
+use crate::cpe::Cpe;
+use crate::model::Key;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+const TEMPLATE: &str = include_str!("viewer.html");
+const PLACEHOLDER: &str = "/*GRAPH*/null";
+
+#[derive(Serialize, Debug)]
+struct Viewer<'a> {
+    sboms: Vec<Sbom<'a>>,
+    nodes: Vec<Node<'a>>,
+    edges: Vec<Edge<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+struct Sbom<'a> {
+    id: &'a str,
+    document_id: &'a str,
+    published: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+struct Node<'a> {
+    id: String,
+    sbom_id: &'a str,
+    node_id: &'a str,
+    name: &'a str,
+    purl: &'a [String],
+    cpe: &'a [String],
+    parsed_cpe: Vec<Cpe>,
+    product_name: Option<&'a str>,
+    product_version: Option<&'a str>,
+    sources: Vec<&'a str>,
+    sbom_ids: BTreeSet<String>,
+    document_ids: BTreeSet<&'a String>,
+}
+
+#[derive(Serialize, Debug)]
+struct Edge<'a> {
+    source: String,
+    target: String,
+    relationship: &'a str,
+    sources: Vec<&'a str>,
+}
+
+impl super::Graph {
+    /// Render a self-contained HTML page, embedding the graph and an interactive viewer.
+    pub fn render_html<W>(self, w: &mut W) -> Result<(), anyhow::Error>
+    where
+        W: std::io::Write,
+    {
+        let mut sboms = vec![];
+        let mut nodes = vec![];
+        let mut edges = vec![];
+
+        for (sbom, sbom_nodes) in self.sorted_nodes() {
+            let sbom_doc = self.sboms.get(sbom).expect("SBOM not found");
+            sboms.push(Sbom {
+                id: sbom,
+                document_id: &sbom_doc.document_id,
+                published: &sbom_doc.published,
+            });
+
+            for node in sbom_nodes {
+                let key = Key {
+                    sbom: sbom.clone(),
+                    node: node.node_id.clone(),
+                };
+                nodes.push(Node {
+                    id: key.to_string(),
+                    sbom_id: sbom,
+                    node_id: &node.node_id,
+                    name: &node.name,
+                    purl: &node.purl,
+                    cpe: &node.cpe,
+                    parsed_cpe: cpes(node),
+                    product_name: node.product_name.as_deref(),
+                    product_version: node.product_version.as_deref(),
+                    sources: self.node_sources(&key).map(String::as_str).collect(),
+                    sbom_ids: self.node_sboms(&key),
+                    document_ids: self.node_documents(&key),
+                });
+            }
+        }
+
+        for ((from, to), rel) in self.sorted_relationships() {
+            edges.push(Edge {
+                source: from.to_string(),
+                target: to.to_string(),
+                relationship: rel,
+                sources: self
+                    .relationship_sources(from, to)
+                    .map(String::as_str)
+                    .collect(),
+            });
+        }
+
+        // the data ends up in a script element, so it must not be able to close it
+        let data = serde_json::to_string(&Viewer {
+            sboms,
+            nodes,
+            edges,
+        })?
+        .replace('<', "\\u003c");
+
+        w.write_all(TEMPLATE.replacen(PLACEHOLDER, &data, 1).as_bytes())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PLACEHOLDER;
+    use crate::graph::test::{graph, node, render, with};
+
+    #[test]
+    fn stable_order() {
+        let render = || render(|graph, w| graph.render_html(w));
+        assert_eq!(render(), render());
+    }
+
+    #[test]
+    fn embed() {
+        let mut evil = node("s1", "b");
+        evil.name = "</script><script>alert(1)</script>".into();
+        let mut output = vec![];
+        graph([with(node("s1", "a"), [evil])])
+            .render_html(&mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(!output.contains(PLACEHOLDER));
+        let start = output
+            .find(r#"<script type="application/json" id="graph">"#)
+            .unwrap();
+        let end = start + output[start..].find("</script>").unwrap();
+        let data = &output[start..end];
+        let data = &data[data.find('>').unwrap() + 1..];
+
+        let data: serde_json::Value = serde_json::from_str(data).unwrap();
+        assert_eq!(data["sboms"][0]["id"], "s1");
+        assert_eq!(
+            data["nodes"][1]["name"],
+            "</script><script>alert(1)</script>"
+        );
+        assert_eq!(data["edges"][0]["source"], "s1--a");
+        assert_eq!(data["edges"][0]["target"], "s1--b");
+        assert_eq!(data["edges"][0]["relationship"], "dependency_of");
+    }
+}
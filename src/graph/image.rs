@@ -0,0 +1,163 @@
+use super::{Graph, Style};
+use anyhow::{Context, anyhow, bail};
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum ImageFormat {
+    Svg,
+    Png,
+    Pdf,
+}
+
+impl ImageFormat {
+    /// Detect the format from the extension of a file name.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "svg" => Some(Self::Svg),
+            "png" => Some(Self::Png),
+            "pdf" => Some(Self::Pdf),
+            _ => None,
+        }
+    }
+}
+
+/// The Graphviz layout engine
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Layout {
+    #[default]
+    Dot,
+    Neato,
+    Fdp,
+    Sfdp,
+    Circo,
+    Twopi,
+}
+
+impl Graph {
+    /// Render an image, by piping the Graphviz output through the `dot` binary.
+    pub fn render_image<W>(
+        self,
+        dot: &Path,
+        layout: Layout,
+        format: ImageFormat,
+        style: &Style,
+        w: &mut W,
+    ) -> anyhow::Result<()>
+    where
+        W: std::io::Write,
+    {
+        let mut child = Command::new(dot)
+            .arg(format!("-K{layout}"))
+            .arg(format!("-T{format}"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| match err.kind() {
+                ErrorKind::NotFound => anyhow!(
+                    "Graphviz binary not found: {}. Install Graphviz, or use --dot to provide its location",
+                    dot.display()
+                ),
+                _ => anyhow::Error::from(err)
+                    .context(format!("failed to run Graphviz: {}", dot.display())),
+            })?;
+
+        let mut stdin = child.stdin.take().expect("stdin must be piped");
+        let style = style.clone();
+        let writer = std::thread::spawn(move || self.render_grapviz(&style, &mut stdin));
+
+        let output = child.wait_with_output()?;
+        let written = writer
+            .join()
+            .map_err(|_| anyhow!("failed to render Graphviz output"))?;
+
+        if !output.status.success() {
+            bail!(
+                "Graphviz failed ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        written.context("failed to pipe Graphviz output")?;
+
+        w.write_all(&output.stdout)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::test::sample;
+
+    #[test]
+    fn format_from_path() {
+        let format = |path: &str| ImageFormat::from_path(Path::new(path));
+        assert_eq!(format("out.svg"), Some(ImageFormat::Svg));
+        assert_eq!(format("dir/out.png"), Some(ImageFormat::Png));
+        assert_eq!(format("out.pdf"), Some(ImageFormat::Pdf));
+        assert_eq!(format("out.dot"), None);
+        assert_eq!(format("svg"), None);
+    }
+
+    /// A fake `dot` binary, running the script
+    #[cfg(unix)]
+    fn dot(dir: &Path, script: &str) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join("dot");
+        std::fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn render(dot: &Path, layout: Layout, format: ImageFormat) -> anyhow::Result<String> {
+        let mut output = vec![];
+        sample().render_image(dot, layout, format, &Style::default(), &mut output)?;
+        Ok(String::from_utf8(output)?)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pipe_through_dot() {
+        let dir = tempfile::tempdir().unwrap();
+        let dot = dot(dir.path(), r#"echo "$@"; cat"#);
+
+        let output = render(&dot, Layout::Sfdp, ImageFormat::Svg).unwrap();
+
+        let (args, graph) = output.split_once('\n').unwrap();
+        assert_eq!(args, "-Ksfdp -Tsvg");
+        assert!(graph.contains("digraph {"));
+        assert!(graph.contains(r#""s1--a" -> "s1--b""#));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dot_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let dot = dot(
+            dir.path(),
+            "cat > /dev/null; echo 'syntax error' >&2; exit 3",
+        );
+
+        let err = render(&dot, Layout::Dot, ImageFormat::Png).unwrap_err();
+
+        assert!(err.to_string().starts_with("Graphviz failed"), "{err}");
+        assert!(err.to_string().ends_with(": syntax error"), "{err}");
+    }
+
+    #[test]
+    fn dot_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let err = render(&dir.path().join("dot"), Layout::Dot, ImageFormat::Pdf).unwrap_err();
+
+        assert!(err.to_string().starts_with("Graphviz binary not found"));
+    }
+}
@@ -0,0 +1,188 @@
+//! JSON output, using the Cytoscape.js elements format.
+//!
+//! SBOMs are compound (parent) nodes, components are nodes having their SBOM as parent. The
+//! schema is documented in the README.
+
+use super::cpes;
+use crate::cpe::Cpe;
+use crate::model::Key;
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+#[derive(Serialize, Debug)]
+struct Json<'a> {
+    elements: Elements<'a>,
+}
+
+#[derive(Serialize, Debug)]
+struct Elements<'a> {
+    nodes: Vec<Element<NodeData<'a>>>,
+    edges: Vec<Element<EdgeData<'a>>>,
+}
+
+#[derive(Serialize, Debug)]
+struct Element<T> {
+    data: T,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum NodeData<'a> {
+    Sbom {
+        /// The SBOM ID
+        id: &'a str,
+        document_id: &'a str,
+        published: &'a str,
+    },
+    Component {
+        /// The encoded [`Key`]
+        id: String,
+        /// The SBOM ID
+        parent: &'a str,
+        sbom_id: &'a str,
+        node_id: &'a str,
+        name: &'a str,
+        purl: &'a [String],
+        cpe: &'a [String],
+        parsed_cpe: Vec<Cpe>,
+        product_name: Option<&'a str>,
+        product_version: Option<&'a str>,
+        sources: Vec<&'a str>,
+        sbom_ids: BTreeSet<String>,
+        document_ids: BTreeSet<&'a String>,
+    },
+}
+
+#[derive(Serialize, Debug)]
+struct EdgeData<'a> {
+    id: String,
+    source: String,
+    target: String,
+    relationship: &'a str,
+    sources: Vec<&'a str>,
+}
+
+impl super::Graph {
+    pub fn render_json<W>(self, w: &mut W) -> Result<(), anyhow::Error>
+    where
+        W: std::io::Write,
+    {
+        let mut nodes = vec![];
+        let mut edges = vec![];
+
+        for (sbom, sbom_nodes) in self.sorted_nodes() {
+            let sbom_doc = self.sboms.get(sbom).expect("SBOM not found");
+
+            nodes.push(Element {
+                data: NodeData::Sbom {
+                    id: sbom,
+                    document_id: &sbom_doc.document_id,
+                    published: &sbom_doc.published,
+                },
+            });
+
+            for node in sbom_nodes {
+                let key = Key {
+                    sbom: sbom.clone(),
+                    node: node.node_id.clone(),
+                };
+                nodes.push(Element {
+                    data: NodeData::Component {
+                        id: key.to_string(),
+                        parent: sbom,
+                        sbom_id: sbom,
+                        node_id: &node.node_id,
+                        name: &node.name,
+                        purl: &node.purl,
+                        cpe: &node.cpe,
+                        parsed_cpe: cpes(node),
+                        product_name: node.product_name.as_deref(),
+                        product_version: node.product_version.as_deref(),
+                        sources: self.node_sources(&key).map(String::as_str).collect(),
+                        sbom_ids: self.node_sboms(&key),
+                        document_ids: self.node_documents(&key),
+                    },
+                });
+            }
+        }
+
+        for ((from, to), rel) in self.sorted_relationships() {
+            edges.push(Element {
+                data: EdgeData {
+                    id: format!("{from}->{to}"),
+                    source: from.to_string(),
+                    target: to.to_string(),
+                    relationship: rel,
+                    sources: self
+                        .relationship_sources(from, to)
+                        .map(String::as_str)
+                        .collect(),
+                },
+            });
+        }
+
+        serde_json::to_writer_pretty(
+            &mut *w,
+            &Json {
+                elements: Elements { nodes, edges },
+            },
+        )?;
+        writeln!(w)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::graph::test::{graph, node, with, with_purls};
+    use serde_json::json;
+
+    #[test]
+    fn render() {
+        let mut a = with_purls("s1", "a", &["pkg:npm/a@1"]);
+        a.cpe.push("cpe:/a:ex:a:1".into());
+        a.product_name = Some("product".into());
+        let mut output = vec![];
+        graph([with(a, [node("s1", "b")])])
+            .render_json(&mut output)
+            .unwrap();
+        let output: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+        let nodes = &output["elements"]["nodes"];
+        assert_eq!(
+            nodes[0]["data"],
+            json!({
+                "kind": "sbom",
+                "id": "s1",
+                "document_id": "doc-s1",
+                "published": "2025-01-01T00:00:00Z",
+            })
+        );
+        let a = &nodes[1]["data"];
+        assert_eq!(a["kind"], "component");
+        assert_eq!(a["id"], "s1--a");
+        assert_eq!(a["parent"], "s1");
+        assert_eq!(a["purl"], json!(["pkg:npm/a@1"]));
+        assert_eq!(a["parsed_cpe"][0]["vendor"], "ex");
+        assert_eq!(a["product_name"], "product");
+        assert_eq!(a["product_version"], json!(null));
+        assert_eq!(a["sources"], json!(["test.json"]));
+        assert_eq!(a["sbom_ids"], json!(["s1"]));
+        assert_eq!(a["document_ids"], json!(["doc-s1"]));
+        assert_eq!(nodes[2]["data"]["id"], "s1--b");
+
+        assert_eq!(
+            output["elements"]["edges"],
+            json!([{
+                "data": {
+                    "id": "s1--a->s1--b",
+                    "source": "s1--a",
+                    "target": "s1--b",
+                    "relationship": "dependency_of",
+                    "sources": ["test.json"],
+                }
+            }])
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use super::Graph;
+use crate::model::{Key, Node};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The relationship of the edges linking the same component in different SBOMs
+pub const SAME_AS: &str = "same_as";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Identity {
+    /// Components having the same PURL
+    Purl,
+    /// Components having the same CPE
+    Cpe,
+}
+
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct Link {
+    /// Link components of different SBOMs with a "same_as" relationship, when they share an
+    /// identity. May be used multiple times.
+    #[arg(long, global = true, value_enum)]
+    pub same_as: Vec<Identity>,
+
+    /// Ignore the qualifiers and subpath when comparing PURLs
+    #[arg(long, global = true, requires = "same_as")]
+    pub ignore_qualifiers: bool,
+
+    /// Ignore the version (as well as qualifiers and subpath) when comparing PURLs
+    #[arg(long, global = true, requires = "same_as")]
+    pub ignore_version: bool,
+}
+
+impl Link {
+    /// Get the identities of a node, which are used for linking it.
+    fn identities(&self, node: &Node) -> BTreeSet<String> {
+        let mut result = BTreeSet::new();
+
+        for identity in &self.same_as {
+            match identity {
+                Identity::Purl => result.extend(node.purl.iter().map(|purl| {
+                    format!(
+                        "purl:{}",
+                        normalize_purl(purl, self.ignore_qualifiers, self.ignore_version)
+                    )
+                })),
+                Identity::Cpe => result.extend(node.cpe.iter().map(|cpe| format!("cpe:{cpe}"))),
+            }
+        }
+
+        result
+    }
+}
+
+/// Strip the qualifiers and subpath, and optionally the version, from a PURL
+fn normalize_purl(purl: &str, ignore_qualifiers: bool, ignore_version: bool) -> &str {
+    let mut result = purl;
+
+    if ignore_qualifiers || ignore_version {
+        result = result.split(['?', '#']).next().unwrap_or(result);
+    }
+
+    if ignore_version {
+        // the version is separated by the first `@` after the last `/`
+        let start = result.rfind('/').unwrap_or(0);
+        if let Some(at) = result[start..].find('@') {
+            result = &result[..start + at];
+        }
+    }
+
+    result
+}
+
+impl Graph {
+    /// Add "same_as" relationships between components of different SBOMs sharing an identity.
+    ///
+    /// All components sharing an identity get linked to the first one of them, in a different SBOM.
+    /// The sources of those relationships are the inputs of both components.
+    pub fn link(&mut self, link: &Link) {
+        if link.same_as.is_empty() {
+            return;
+        }
+
+        let mut identities = BTreeMap::<String, BTreeSet<Key>>::new();
+        for node in self.nodes.values().flat_map(|nodes| nodes.values()) {
+            for identity in link.identities(node) {
+                identities
+                    .entry(identity)
+                    .or_default()
+                    .insert(node.as_key());
+            }
+        }
+
+        for keys in identities.into_values() {
+            let Some(first) = keys.first() else {
+                continue;
+            };
+            // components of the first SBOM are linked to the first one of another SBOM
+            let other = keys.iter().find(|key| key.sbom != first.sbom);
+
+            for key in keys.iter().skip(1) {
+                let target = match key.sbom == first.sbom {
+                    true => other,
+                    false => Some(first),
+                };
+
+                if let Some(target) = target
+                    && let Entry::Vacant(entry) =
+                        self.relationships.entry((target.clone(), key.clone()))
+                {
+                    let sources = [target, key]
+                        .into_iter()
+                        .flat_map(|key| self.node_sources.get(key).into_iter().flatten())
+                        .cloned()
+                        .collect();
+                    self.relationship_sources
+                        .insert(entry.key().clone(), sources);
+                    entry.insert(SAME_AS.to_string());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::test::{graph, key, node, with, with_purls};
+    use crate::input::Input;
+    use crate::model::PaginatedResult;
+
+    fn link(same_as: Identity) -> Link {
+        Link {
+            same_as: vec![same_as],
+            ..Default::default()
+        }
+    }
+
+    fn same_as(graph: &Graph) -> Vec<(String, String)> {
+        let mut result = graph
+            .relationships
+            .iter()
+            .filter(|(_, rel)| *rel == SAME_AS)
+            .map(|((from, to), _)| (from.to_string(), to.to_string()))
+            .collect::<Vec<_>>();
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn normalize() {
+        let purl = "pkg:maven/org.ex/lib@1.0?type=jar#sub";
+        assert_eq!(normalize_purl(purl, false, false), purl);
+        assert_eq!(
+            normalize_purl(purl, true, false),
+            "pkg:maven/org.ex/lib@1.0"
+        );
+        assert_eq!(normalize_purl(purl, false, true), "pkg:maven/org.ex/lib");
+        assert_eq!(
+            normalize_purl("pkg:npm/%40s/dep@2", false, true),
+            "pkg:npm/%40s/dep"
+        );
+        assert_eq!(normalize_purl("pkg:npm/dep", false, true), "pkg:npm/dep");
+    }
+
+    #[test]
+    fn link_by_purl() {
+        let mut graph = graph([
+            with(
+                with_purls("s1", "a", &["pkg:maven/org.ex/lib@1.0"]),
+                [with_purls("s1", "b", &["pkg:maven/org.ex/lib@1.0"])],
+            ),
+            with_purls("s2", "x", &["pkg:maven/org.ex/lib@1.0"]),
+            with_purls("s3", "y", &["pkg:maven/org.ex/lib@2.0"]),
+        ]);
+
+        graph.link(&link(Identity::Purl));
+
+        assert_eq!(
+            same_as(&graph),
+            vec![
+                ("s1--a".into(), "s2--x".into()),
+                ("s2--x".into(), "s1--b".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn same_sbom_only() {
+        let mut graph = graph([
+            with_purls("s1", "a", &["pkg:maven/org.ex/lib@1.0"]),
+            with_purls("s1", "b", &["pkg:maven/org.ex/lib@1.0"]),
+        ]);
+
+        graph.link(&link(Identity::Purl));
+
+        assert!(same_as(&graph).is_empty());
+    }
+
+    #[test]
+    fn ignore_version() {
+        let mut graph = graph([
+            with_purls("s1", "a", &["pkg:maven/org.ex/lib@1.0"]),
+            with_purls("s2", "x", &["pkg:maven/org.ex/lib@2.0"]),
+        ]);
+
+        graph.link(&Link {
+            ignore_version: true,
+            ..link(Identity::Purl)
+        });
+
+        assert_eq!(same_as(&graph), vec![("s1--a".into(), "s2--x".into())]);
+    }
+
+    #[test]
+    fn link_by_cpe() {
+        let mut a = node("s1", "a");
+        a.cpe.push("cpe:/a:ex:lib:1.0".into());
+        let mut x = node("s2", "x");
+        x.cpe.push("cpe:/a:ex:lib:1.0".into());
+        let mut graph = graph([a, x]);
+
+        graph.link(&link(Identity::Purl));
+        assert!(same_as(&graph).is_empty());
+
+        graph.link(&link(Identity::Cpe));
+        assert_eq!(same_as(&graph), vec![("s1--a".into(), "s2--x".into())]);
+    }
+
+    #[test]
+    fn sources() {
+        let input = |source: &str, node: Node| Input {
+            source: source.into(),
+            result: PaginatedResult {
+                items: vec![node],
+                total: 1,
+            },
+        };
+        let mut graph = Graph::new(&[
+            input(
+                "a.json",
+                with_purls("s1", "a", &["pkg:maven/org.ex/lib@1.0"]),
+            ),
+            input(
+                "b.json",
+                with_purls("s2", "x", &["pkg:maven/org.ex/lib@1.0"]),
+            ),
+        ]);
+
+        graph.link(&link(Identity::Purl));
+
+        assert_eq!(
+            graph
+                .relationship_sources(&key("s1", "a"), &key("s2", "x"))
+                .collect::<Vec<_>>(),
+            vec!["a.json", "b.json"]
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use super::Graph;
+use crate::model::{Key, Node};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The identity to merge components by
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum MergeBy {
+    /// Components sharing a PURL
+    Purl,
+    /// Components sharing a CPE
+    Cpe,
+    /// Components having the same name
+    Name,
+}
+
+impl MergeBy {
+    fn identities<'a>(&self, node: &'a Node) -> Vec<&'a String> {
+        match self {
+            Self::Purl => node.purl.iter().collect(),
+            Self::Cpe => node.cpe.iter().collect(),
+            Self::Name => vec![&node.name],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct Merge {
+    /// Merge the components sharing an identity into a single node, even across SBOMs
+    #[arg(long, global = true, value_enum)]
+    pub merge_by: Option<MergeBy>,
+}
+
+/// Find the representative of a key, which is the smallest key it got merged with
+fn representative(parents: &HashMap<Key, Key>, key: &Key) -> Key {
+    let mut key = key;
+    while let Some(parent) = parents.get(key) {
+        key = parent;
+    }
+    key.clone()
+}
+
+fn extend_unique(target: &mut Vec<String>, values: &[String]) {
+    for value in values {
+        if !target.contains(value) {
+            target.push(value.clone());
+        }
+    }
+}
+
+impl Graph {
+    /// Merge components sharing an identity into a single node, even across SBOMs.
+    ///
+    /// The merged node keeps the key of the first component, and records all SBOMs the
+    /// components appear in. Relationships between merged components are deduplicated.
+    pub fn merge(&mut self, merge: &Merge) {
+        let Some(by) = merge.merge_by else {
+            return;
+        };
+
+        let mut keys = self
+            .nodes
+            .values()
+            .flat_map(|nodes| nodes.values())
+            .map(Node::as_key)
+            .collect::<Vec<_>>();
+        keys.sort();
+
+        // union components sharing an identity, the smallest key being the representative
+        let mut parents = HashMap::<Key, Key>::new();
+        let mut identities = HashMap::<String, Key>::new();
+        for key in &keys {
+            let Some(node) = self.node(key) else {
+                continue;
+            };
+
+            for identity in by.identities(node) {
+                match identities.get(identity) {
+                    Some(other) => {
+                        let a = representative(&parents, key);
+                        let b = representative(&parents, other);
+                        if a != b {
+                            let (min, max) = if a < b { (a, b) } else { (b, a) };
+                            parents.insert(max, min);
+                        }
+                    }
+                    None => {
+                        identities.insert(identity.clone(), key.clone());
+                    }
+                }
+            }
+        }
+
+        let mapping = keys
+            .iter()
+            .map(|key| (key.clone(), representative(&parents, key)))
+            .filter(|(key, rep)| key != rep)
+            .collect::<BTreeMap<_, _>>();
+        if mapping.is_empty() {
+            return;
+        }
+        let map = |key: &Key| mapping.get(key).unwrap_or(key).clone();
+
+        for (key, rep) in &mapping {
+            let Some(node) = self
+                .nodes
+                .get_mut(&key.sbom)
+                .and_then(|n| n.remove(&key.node))
+            else {
+                continue;
+            };
+
+            let mut sboms = self.node_sboms.remove(key).unwrap_or_default();
+            sboms.insert(key.sbom.clone());
+            let target = self.node_sboms.entry(rep.clone()).or_default();
+            target.insert(rep.sbom.clone());
+            target.extend(sboms);
+
+            let sources = self.node_sources.remove(key).unwrap_or_default();
+            self.node_sources
+                .entry(rep.clone())
+                .or_default()
+                .extend(sources);
+
+            if let Some(target) = self
+                .nodes
+                .get_mut(&rep.sbom)
+                .and_then(|n| n.get_mut(&rep.node))
+            {
+                extend_unique(&mut target.purl, &node.purl);
+                extend_unique(&mut target.cpe, &node.cpe);
+            }
+        }
+        self.nodes.retain(|_, nodes| !nodes.is_empty());
+
+        let mut relationships = self.relationships.drain().collect::<Vec<_>>();
+        relationships.sort();
+        for ((from, to), rel) in relationships {
+            let edge = (map(&from), map(&to));
+            let sources = self
+                .relationship_sources
+                .remove(&(from, to))
+                .unwrap_or_default();
+
+            if edge.0 == edge.1 {
+                continue;
+            }
+
+            self.relationship_sources
+                .entry(edge.clone())
+                .or_default()
+                .extend(sources);
+            self.relationships.entry(edge).or_insert(rel);
+        }
+
+        self.roots = self.roots.iter().map(map).collect::<BTreeSet<_>>();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::test::{graph, key, keys, node, with, with_purls};
+
+    fn merge(by: MergeBy) -> Merge {
+        Merge { merge_by: Some(by) }
+    }
+
+    fn relationships(graph: &Graph) -> Vec<(String, String)> {
+        graph
+            .sorted_relationships()
+            .into_iter()
+            .map(|((from, to), _)| (from.to_string(), to.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn across_sboms() {
+        let mut graph = graph([
+            with(
+                with_purls("s1", "a", &["pkg:maven/org.ex/app@1"]),
+                [with_purls("s1", "b", &["pkg:maven/org.ex/lib@1"])],
+            ),
+            with(
+                with_purls("s2", "x", &["pkg:maven/org.ex/other@1"]),
+                [with_purls("s2", "y", &["pkg:maven/org.ex/lib@1"])],
+            ),
+        ]);
+
+        graph.merge(&merge(MergeBy::Purl));
+
+        assert_eq!(keys(&graph), vec!["s1--a", "s1--b", "s2--x"]);
+        assert_eq!(
+            relationships(&graph),
+            vec![
+                ("s1--a".into(), "s1--b".into()),
+                ("s2--x".into(), "s1--b".into()),
+            ]
+        );
+        assert_eq!(
+            graph.node_sboms(&key("s1", "b")),
+            BTreeSet::from(["s1".into(), "s2".into()])
+        );
+        assert_eq!(
+            graph.node_sboms(&key("s1", "a")),
+            BTreeSet::from(["s1".into()])
+        );
+        assert_eq!(
+            graph.node_documents(&key("s1", "b")),
+            BTreeSet::from([&"doc-s1".into(), &"doc-s2".into()])
+        );
+    }
+
+    #[test]
+    fn deduplicate_relationships() {
+        let mut graph = graph([
+            with(
+                with_purls("s1", "a", &["pkg:npm/app@1"]),
+                [with_purls("s1", "b", &["pkg:npm/lib@1"])],
+            ),
+            with(
+                with_purls("s2", "x", &["pkg:npm/app@1"]),
+                [
+                    with_purls("s2", "y", &["pkg:npm/lib@1"]),
+                    with_purls("s2", "z", &["pkg:npm/app@1"]),
+                ],
+            ),
+        ]);
+
+        graph.merge(&merge(MergeBy::Purl));
+
+        assert_eq!(keys(&graph), vec!["s1--a", "s1--b"]);
+        // the relationship to z became a self-loop, and got dropped
+        assert_eq!(
+            relationships(&graph),
+            vec![("s1--a".into(), "s1--b".into())]
+        );
+        assert_eq!(graph.roots, BTreeSet::from([key("s1", "a")]));
+    }
+
+    #[test]
+    fn transitive() {
+        let mut graph = graph([
+            with_purls("s1", "a", &["pkg:npm/a@1"]),
+            with_purls("s2", "b", &["pkg:npm/a@1", "pkg:npm/b@1"]),
+            with_purls("s3", "c", &["pkg:npm/b@1"]),
+        ]);
+
+        graph.merge(&merge(MergeBy::Purl));
+
+        assert_eq!(keys(&graph), vec!["s1--a"]);
+        let node = graph.node(&key("s1", "a")).unwrap();
+        assert_eq!(node.purl, vec!["pkg:npm/a@1", "pkg:npm/b@1"]);
+        assert_eq!(graph.node_sboms(&key("s1", "a")).len(), 3);
+    }
+
+    #[test]
+    fn by_name() {
+        let mut graph = graph([node("s1", "a"), node("s2", "a"), node("s2", "b")]);
+
+        graph.merge(&merge(MergeBy::Cpe));
+        assert_eq!(keys(&graph).len(), 3);
+
+        graph.merge(&merge(MergeBy::Name));
+        assert_eq!(keys(&graph), vec!["s1--a", "s2--b"]);
+    }
+}
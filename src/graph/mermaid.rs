@@ -0,0 +1,90 @@
+use super::Graph;
+use crate::model::Key;
+use std::collections::BTreeMap;
+
+/// Escape a string for use inside a quoted mermaid label
+fn encode(s: impl AsRef<str>) -> String {
+    s.as_ref()
+        .replace('#', "#35;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+impl Graph {
+    pub fn render_mermaid<W>(self, w: &mut W) -> Result<(), std::io::Error>
+    where
+        W: std::io::Write,
+    {
+        writeln!(w, "flowchart LR")?;
+
+        // mermaid IDs are rather limited, so we use generated ones, in a stable order
+        let mut ids = BTreeMap::<Key, String>::new();
+
+        for (n, (sbom, nodes)) in self.sorted_nodes().into_iter().enumerate() {
+            let sbom_doc = self.sboms.get(sbom).expect("SBOM not found");
+
+            writeln!(
+                w,
+                r#"  subgraph s{n}["{label}"]"#,
+                label = encode(format!(
+                    "{} / {} / {}",
+                    sbom_doc.document_id, sbom_doc.published, sbom
+                ))
+            )?;
+
+            for node in nodes {
+                let id = format!("n{}", ids.len());
+                writeln!(w, r#"    {id}["{label}"]"#, label = encode(&node.name))?;
+                ids.insert(node.as_key(), id);
+            }
+
+            writeln!(w, "  end")?;
+        }
+
+        for ((from, to), rel) in self.sorted_relationships() {
+            let (Some(from), Some(to)) = (ids.get(from), ids.get(to)) else {
+                continue;
+            };
+
+            writeln!(w, r#"  {from} -->|"{label}"| {to}"#, label = encode(rel))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::test::{graph, node, with};
+
+    #[test]
+    fn encoding() {
+        assert_eq!(encode(r#"a "b" <c> #1"#), "a #quot;b#quot; #lt;c#gt; #35;1");
+    }
+
+    #[test]
+    fn render() {
+        let mut b = node("s1", "b");
+        b.name = r#"lib "b""#.into();
+        let mut output = vec![];
+        graph([with(node("s1", "a"), [b]), node("s2", "x")])
+            .render_mermaid(&mut output)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"flowchart LR
+  subgraph s0["doc-s1 / 2025-01-01T00:00:00Z / s1"]
+    n0["a"]
+    n1["lib #quot;b#quot;"]
+  end
+  subgraph s1["doc-s2 / 2025-01-01T00:00:00Z / s2"]
+    n2["x"]
+  end
+  n0 -->|"dependency_of"| n1
+"#
+        );
+    }
+}
@@ -1,9 +1,69 @@
+mod csv;
+mod cypher;
+mod explore;
+mod filter;
+mod focus;
 mod gexf;
 mod graphml;
 mod graphviz;
+mod html;
+mod image;
+mod json;
+mod link;
+mod merge;
+mod mermaid;
+mod path;
+mod plantuml;
+mod style;
+mod template;
+#[cfg(test)]
+mod test;
+mod tree;
 
+pub use filter::Filter;
+pub use focus::Focus;
+pub use image::{ImageFormat, Layout};
+pub use link::Link;
+pub use merge::Merge;
+pub use style::Style;
+pub use tree::Tree;
+
+use crate::cpe::Cpe;
+use crate::input::Input;
 use crate::model::{Key, Node};
-use std::collections::HashMap;
+use crate::purl::Purl;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Graphml,
+    Gexf,
+    Graphviz,
+    Html,
+    Mermaid,
+    Plantuml,
+    Json,
+    Cypher,
+}
+
+impl Format {
+    /// Detect the format from the extension of a file name.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "graphml" => Some(Self::Graphml),
+            "gexf" => Some(Self::Gexf),
+            "dot" | "gv" => Some(Self::Graphviz),
+            "html" | "htm" => Some(Self::Html),
+            "mmd" | "mermaid" => Some(Self::Mermaid),
+            "puml" | "plantuml" | "pu" => Some(Self::Plantuml),
+            "json" => Some(Self::Json),
+            "cypher" | "cql" => Some(Self::Cypher),
+            _ => None,
+        }
+    }
+}
 
 struct Sbom {
     document_id: String,
@@ -14,73 +74,314 @@ pub struct Graph {
     sboms: HashMap<String, Sbom>,
     nodes: HashMap<String, HashMap<String, Node>>,
     relationships: HashMap<(Key, Key), String>,
+    /// The inputs each node was found in
+    node_sources: HashMap<Key, BTreeSet<String>>,
+    /// The inputs each relationship was found in
+    relationship_sources: HashMap<(Key, Key), BTreeSet<String>>,
+    /// The top-level items of the analysis results
+    roots: BTreeSet<Key>,
+    /// The SBOMs merged nodes appear in
+    node_sboms: HashMap<Key, BTreeSet<String>>,
 }
 
 impl Graph {
-    pub fn new(input: &[Node]) -> Self {
-        let mut sboms = HashMap::new();
-        let mut nodes = HashMap::new();
-        let mut relationships = HashMap::new();
+    pub fn new(inputs: &[Input]) -> Self {
+        let mut graph = Self {
+            sboms: HashMap::new(),
+            nodes: HashMap::new(),
+            relationships: HashMap::new(),
+            node_sources: HashMap::new(),
+            relationship_sources: HashMap::new(),
+            roots: BTreeSet::new(),
+            node_sboms: HashMap::new(),
+        };
 
         fn scan(
-            sboms: &mut HashMap<String, Sbom>,
-            nodes: &mut HashMap<String, HashMap<String, Node>>,
-            relationships: &mut HashMap<(Key, Key), String>,
+            graph: &mut Graph,
+            source: &str,
             input: &[Node],
             parent: Option<&Node>,
             reverse: bool,
         ) {
             for node in input {
-                sboms.entry(node.sbom_id.clone()).or_insert_with(|| Sbom {
-                    document_id: node.document_id.clone(),
-                    published: node.published.clone(),
-                });
+                graph
+                    .sboms
+                    .entry(node.sbom_id.clone())
+                    .or_insert_with(|| Sbom {
+                        document_id: node.document_id.clone(),
+                        published: node.published.clone(),
+                    });
 
                 if let (Some(parent), Some(rel)) = (parent, &node.relationship) {
-                    if reverse {
-                        relationships.insert((node.as_key(), parent.as_key()), rel.clone());
+                    let edge = if reverse {
+                        (node.as_key(), parent.as_key())
                     } else {
-                        relationships.insert((parent.as_key(), node.as_key()), rel.clone());
-                    }
+                        (parent.as_key(), node.as_key())
+                    };
+
+                    graph
+                        .relationship_sources
+                        .entry(edge.clone())
+                        .or_default()
+                        .insert(source.to_string());
+                    graph.relationships.insert(edge, rel.clone());
+                }
+
+                if parent.is_none() {
+                    graph.roots.insert(node.as_key());
                 }
 
-                nodes
+                graph
+                    .node_sources
+                    .entry(node.as_key())
+                    .or_default()
+                    .insert(source.to_string());
+
+                graph
+                    .nodes
                     .entry(node.sbom_id.clone())
                     .or_default()
                     .insert(node.node_id.clone(), node.clone());
 
-                scan(
-                    sboms,
-                    nodes,
-                    relationships,
-                    &node.ancestors,
-                    Some(node),
-                    true,
-                );
-                scan(
-                    sboms,
-                    nodes,
-                    relationships,
-                    &node.descendants,
-                    Some(node),
-                    false,
+                scan(graph, source, &node.ancestors, Some(node), true);
+                scan(graph, source, &node.descendants, Some(node), false);
+            }
+        }
+
+        for input in inputs {
+            scan(&mut graph, &input.source, &input.result.items, None, false);
+        }
+
+        graph
+    }
+
+    pub fn render<W>(self, format: Format, style: &Style, w: &mut W) -> anyhow::Result<()>
+    where
+        W: std::io::Write,
+    {
+        match format {
+            Format::Graphml => self.render_graphml(style, w),
+            Format::Gexf => self.render_gexf(style, w),
+            Format::Graphviz => Ok(self.render_grapviz(style, w)?),
+            Format::Html => self.render_html(w),
+            Format::Mermaid => Ok(self.render_mermaid(w)?),
+            Format::Plantuml => Ok(self.render_plantuml(w)?),
+            Format::Json => self.render_json(w),
+            Format::Cypher => Ok(self.render_cypher(w)?),
+        }
+    }
+
+    /// Only keep the nodes matching the predicate, along with the relationships between them.
+    fn retain<F>(&mut self, f: F)
+    where
+        F: Fn(&Key) -> bool,
+    {
+        for (sbom, nodes) in &mut self.nodes {
+            nodes.retain(|node, _| {
+                f(&Key {
+                    sbom: sbom.clone(),
+                    node: node.clone(),
+                })
+            });
+        }
+        self.nodes.retain(|_, nodes| !nodes.is_empty());
+        self.node_sboms.retain(|key, _| f(key));
+        self.sboms.retain(|sbom, _| {
+            self.nodes.contains_key(sbom) || self.node_sboms.values().any(|s| s.contains(sbom))
+        });
+
+        self.retain_relationships(|from, to| f(from) && f(to));
+        self.node_sources.retain(|key, _| f(key));
+        self.roots.retain(|key| f(key));
+    }
+
+    /// Only keep the relationships matching the predicate.
+    fn retain_relationships<F>(&mut self, f: F)
+    where
+        F: Fn(&Key, &Key) -> bool,
+    {
+        self.relationships.retain(|(from, to), _| f(from, to));
+        self.relationship_sources
+            .retain(|(from, to), _| f(from, to));
+    }
+
+    /// Find all nodes reachable from the start nodes, following relationships forward (from
+    /// ancestor to descendant) or backward, optionally limited to a number of hops.
+    fn reachable<'a, F>(
+        &self,
+        start: impl IntoIterator<Item = &'a Key>,
+        forward: bool,
+        depth: Option<usize>,
+        allowed: F,
+    ) -> HashSet<Key>
+    where
+        F: Fn(&Key) -> bool,
+    {
+        let mut edges = HashMap::<&Key, Vec<&Key>>::new();
+        for (from, to) in self.edges() {
+            match forward {
+                true => edges.entry(from).or_default().push(to),
+                false => edges.entry(to).or_default().push(from),
+            }
+        }
+
+        let mut result = HashSet::new();
+        let mut queue = start
+            .into_iter()
+            .filter(|key| allowed(key))
+            .map(|key| (key, 0))
+            .collect::<VecDeque<_>>();
+
+        while let Some((next, level)) = queue.pop_front() {
+            if result.insert(next.clone()) && depth.is_none_or(|depth| level < depth) {
+                queue.extend(
+                    edges
+                        .get(next)
+                        .into_iter()
+                        .flatten()
+                        .copied()
+                        .filter(|key| allowed(key))
+                        .map(|key| (key, level + 1)),
                 );
             }
         }
 
-        scan(
-            &mut sboms,
-            &mut nodes,
-            &mut relationships,
-            input,
-            None,
-            false,
-        );
-
-        Self {
-            sboms,
-            nodes,
-            relationships,
+        result
+    }
+
+    /// The relationships to follow from ancestor to descendant.
+    ///
+    /// Having no direction, `same_as` relationships are followed both ways.
+    fn edges(&self) -> impl Iterator<Item = (&Key, &Key)> {
+        self.relationships.iter().flat_map(|((from, to), rel)| {
+            let reverse = (rel == link::SAME_AS).then_some((to, from));
+            std::iter::once((from, to)).chain(reverse)
+        })
+    }
+
+    /// The relationship between two components, following `same_as` relationships both ways.
+    fn relationship(&self, from: &Key, to: &Key) -> Option<&String> {
+        self.relationships
+            .get(&(from.clone(), to.clone()))
+            .or_else(|| {
+                self.relationships
+                    .get(&(to.clone(), from.clone()))
+                    .filter(|rel| *rel == link::SAME_AS)
+            })
+    }
+
+    /// Find all nodes matching the selector, either by key (`<sbom>--<node>`), PURL or name.
+    fn find(&self, selector: &str) -> Vec<Key> {
+        let mut result = self
+            .nodes
+            .values()
+            .flat_map(|nodes| nodes.values())
+            .filter(|node| {
+                node.as_key().to_string() == selector
+                    || node.purl.iter().any(|purl| purl == selector)
+                    || node.name == selector
+            })
+            .map(Node::as_key)
+            .collect::<Vec<_>>();
+        result.sort();
+        result
+    }
+
+    /// All PURLs which can't be parsed, along with their component and the reason.
+    pub fn malformed_purls(&self) -> Vec<(Key, &String, anyhow::Error)> {
+        self.malformed::<Purl>(|node| &node.purl)
+    }
+
+    /// All CPEs which can't be parsed, along with their component and the reason.
+    pub fn malformed_cpes(&self) -> Vec<(Key, &String, anyhow::Error)> {
+        self.malformed::<Cpe>(|node| &node.cpe)
+    }
+
+    fn malformed<T>(&self, values: fn(&Node) -> &Vec<String>) -> Vec<(Key, &String, anyhow::Error)>
+    where
+        T: FromStr<Err = anyhow::Error>,
+    {
+        let mut result = self
+            .nodes
+            .values()
+            .flat_map(|nodes| nodes.values())
+            .flat_map(|node| {
+                values(node).iter().filter_map(|value| {
+                    value
+                        .parse::<T>()
+                        .err()
+                        .map(|err| (node.as_key(), value, err))
+                })
+            })
+            .collect::<Vec<_>>();
+        result.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+        result
+    }
+
+    fn node(&self, key: &Key) -> Option<&Node> {
+        self.nodes.get(&key.sbom)?.get(&key.node)
+    }
+
+    /// The nodes, grouped by SBOM, in a stable order.
+    fn sorted_nodes(&self) -> Vec<(&String, Vec<&Node>)> {
+        let mut result = self
+            .nodes
+            .iter()
+            .map(|(sbom, nodes)| {
+                let mut nodes = nodes.values().collect::<Vec<_>>();
+                nodes.sort_by_key(|node| &node.node_id);
+                (sbom, nodes)
+            })
+            .collect::<Vec<_>>();
+        result.sort_by_key(|(sbom, _)| *sbom);
+        result
+    }
+
+    /// The relationships, in a stable order.
+    fn sorted_relationships(&self) -> Vec<(&(Key, Key), &String)> {
+        let mut result = self.relationships.iter().collect::<Vec<_>>();
+        result.sort();
+        result
+    }
+
+    fn node_sources(&self, key: &Key) -> impl Iterator<Item = &String> {
+        self.node_sources.get(key).into_iter().flatten()
+    }
+
+    /// The SBOMs a node appears in, more than one for merged nodes.
+    fn node_sboms(&self, key: &Key) -> BTreeSet<String> {
+        match self.node_sboms.get(key) {
+            Some(sboms) => sboms.clone(),
+            None => BTreeSet::from([key.sbom.clone()]),
         }
     }
+
+    /// The documents a node appears in, more than one for merged nodes.
+    fn node_documents(&self, key: &Key) -> BTreeSet<&String> {
+        self.node_sboms(key)
+            .iter()
+            .filter_map(|sbom| self.sboms.get(sbom))
+            .map(|sbom| &sbom.document_id)
+            .collect()
+    }
+
+    fn relationship_sources(&self, from: &Key, to: &Key) -> impl Iterator<Item = &String> {
+        self.relationship_sources
+            .get(&(from.clone(), to.clone()))
+            .into_iter()
+            .flatten()
+    }
+}
+
+/// The PURLs of a node, skipping malformed ones.
+fn purls(node: &Node) -> Vec<Purl> {
+    node.purl
+        .iter()
+        .filter_map(|purl| purl.parse().ok())
+        .collect()
+}
+
+/// The CPEs of a node, skipping malformed ones.
+fn cpes(node: &Node) -> Vec<Cpe> {
+    node.cpe.iter().filter_map(|cpe| cpe.parse().ok()).collect()
 }
@@ -0,0 +1,348 @@
+use super::Graph;
+use crate::model::Key;
+use anyhow::bail;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Write;
+
+/// A depth-first search for paths of a given length, sharing a single path.
+struct Search<'a> {
+    edges: HashMap<&'a Key, Vec<&'a Key>>,
+    /// The number of hops from a component to the closest target
+    distances: HashMap<&'a Key, usize>,
+    targets: &'a HashSet<Key>,
+    limit: Option<usize>,
+    path: Vec<&'a Key>,
+    visited: HashSet<&'a Key>,
+    result: Vec<Vec<Key>>,
+    /// Whether paths were cut short by the length, so longer ones might exist
+    deeper: bool,
+}
+
+impl<'a> Search<'a> {
+    /// Check if enough paths were found
+    fn done(&self) -> bool {
+        self.limit.is_some_and(|limit| self.result.len() >= limit)
+    }
+
+    fn run(&mut self, start: &'a Key, length: usize) {
+        self.path.push(start);
+        self.visited.insert(start);
+        self.step(length);
+        self.visited.remove(start);
+        self.path.pop();
+    }
+
+    fn step(&mut self, remaining: usize) {
+        let last = self.path[self.path.len() - 1];
+        let target = self.path.len() > 1 && self.targets.contains(last);
+
+        if remaining == 0 {
+            match target {
+                true => self
+                    .result
+                    .push(self.path.iter().copied().cloned().collect()),
+                false => self.deeper = true,
+            }
+            return;
+        }
+        if target {
+            return;
+        }
+
+        let edges = self.edges.get(last).cloned().unwrap_or_default();
+        for next in edges {
+            if self.done() {
+                return;
+            }
+            if self.visited.contains(next) {
+                continue;
+            }
+            match self.distances.get(next) {
+                Some(distance) if *distance < remaining => {}
+                Some(_) => {
+                    self.deeper = true;
+                    continue;
+                }
+                None => continue,
+            }
+
+            self.run(next, remaining - 1);
+        }
+    }
+}
+
+impl Graph {
+    /// Find the paths between two components, selected by key, PURL or name.
+    ///
+    /// Paths follow the relationships from ancestor to descendant, as well as `same_as`
+    /// relationships in both directions, and don't visit a component twice. They are ordered by length, shortest first, and only up to `limit` paths are
+    /// returned, if set.
+    pub fn paths(
+        &self,
+        from: &str,
+        to: &str,
+        limit: Option<usize>,
+    ) -> anyhow::Result<Vec<Vec<Key>>> {
+        let sources = self.find(from);
+        if sources.is_empty() {
+            bail!("no component matches '{from}'");
+        }
+        let targets = self.find(to).into_iter().collect::<HashSet<_>>();
+        if targets.is_empty() {
+            bail!("no component matches '{to}'");
+        }
+
+        let mut edges = HashMap::<&Key, Vec<&Key>>::new();
+        let mut incoming = HashMap::<&Key, Vec<&Key>>::new();
+        for (from, to) in self.edges() {
+            edges.entry(from).or_default().push(to);
+            incoming.entry(to).or_default().push(from);
+        }
+        for next in edges.values_mut() {
+            next.sort();
+        }
+
+        // breadth first, backwards from the targets. Components without a distance can't reach
+        // any target, and aren't worth visiting.
+        let mut distances = targets
+            .iter()
+            .map(|key| (key, 0))
+            .collect::<HashMap<_, _>>();
+        let mut queue = targets.iter().collect::<VecDeque<_>>();
+        while let Some(next) = queue.pop_front() {
+            let distance = distances[next] + 1;
+            for prev in incoming.get(next).into_iter().flatten() {
+                if !distances.contains_key(prev) {
+                    distances.insert(prev, distance);
+                    queue.push_back(prev);
+                }
+            }
+        }
+
+        let mut search = Search {
+            edges,
+            distances,
+            targets: &targets,
+            limit,
+            path: vec![],
+            visited: HashSet::new(),
+            result: vec![],
+            deeper: true,
+        };
+
+        // iterative deepening, finding the shorter paths first, without keeping more than the
+        // current path around
+        let mut length = 1;
+        while search.deeper && !search.done() {
+            search.deeper = false;
+            for source in &sources {
+                if !search.done() && search.distances.contains_key(source) {
+                    search.run(source, length);
+                }
+            }
+            length += 1;
+        }
+
+        if search.result.is_empty() {
+            bail!("no path from '{from}' to '{to}', paths lead from an ancestor to a descendant");
+        }
+
+        Ok(search.result)
+    }
+
+    /// Only keep the components and relationships which are part of one of the paths.
+    pub fn retain_paths(&mut self, paths: &[Vec<Key>]) {
+        let nodes = paths.iter().flatten().cloned().collect::<HashSet<_>>();
+        let edges = paths
+            .iter()
+            .flat_map(|path| path.windows(2))
+            .map(|edge| (edge[0].clone(), edge[1].clone()))
+            // `same_as` relationships might have been followed backwards
+            .map(|edge| match self.relationships.contains_key(&edge) {
+                true => edge,
+                false => (edge.1, edge.0),
+            })
+            .collect::<HashSet<_>>();
+
+        self.retain(|key| nodes.contains(key));
+        self.retain_relationships(|from, to| edges.contains(&(from.clone(), to.clone())));
+    }
+
+    /// Print the paths, one per line, with the relationships between the components.
+    pub fn render_paths<W>(&self, paths: &[Vec<Key>], w: &mut W) -> Result<(), std::io::Error>
+    where
+        W: Write,
+    {
+        let label = |key: &Key| match self.node(key) {
+            Some(node) => format!("{} ({key})", node.name),
+            None => key.to_string(),
+        };
+
+        for path in paths {
+            let Some(first) = path.first() else {
+                continue;
+            };
+
+            let mut line = label(first);
+            for edge in path.windows(2) {
+                let rel = self
+                    .relationship(&edge[0], &edge[1])
+                    .map(String::as_str)
+                    .unwrap_or_default();
+                line.push_str(&format!(" -[{rel}]-> {}", label(&edge[1])));
+            }
+
+            writeln!(w, "{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::graph::Link;
+    use crate::graph::link::Identity;
+    use crate::graph::test::{graph, key, node, with, with_purls};
+    use crate::model::Key;
+
+    fn keys(paths: Vec<Vec<Key>>) -> Vec<Vec<String>> {
+        paths
+            .into_iter()
+            .map(|path| path.into_iter().map(|key| key.node).collect())
+            .collect()
+    }
+
+    /// r -> c -> x, r -> d -> e -> x
+    fn diamond() -> crate::graph::Graph {
+        graph([with(
+            node("s", "r"),
+            [
+                with(node("s", "c"), [node("s", "x")]),
+                with(node("s", "d"), [with(node("s", "e"), [node("s", "x")])]),
+            ],
+        )])
+    }
+
+    #[test]
+    fn shortest_first() {
+        let paths = diamond().paths("r", "x", Some(10)).unwrap();
+        assert_eq!(
+            keys(paths),
+            vec![vec!["r", "c", "x"], vec!["r", "d", "e", "x"]]
+        );
+    }
+
+    #[test]
+    fn limit() {
+        let paths = diamond().paths("r", "x", Some(1)).unwrap();
+        assert_eq!(keys(paths), vec![vec!["r", "c", "x"]]);
+    }
+
+    #[test]
+    fn unlimited() {
+        let paths = diamond().paths("r", "x", None).unwrap();
+        assert_eq!(paths.len(), 2);
+    }
+
+    #[test]
+    fn by_key() {
+        let paths = diamond().paths("s--d", "s--x", Some(10)).unwrap();
+        assert_eq!(keys(paths), vec![vec!["d", "e", "x"]]);
+    }
+
+    #[test]
+    fn only_downwards() {
+        assert!(diamond().paths("x", "r", Some(10)).is_err());
+        assert!(diamond().paths("c", "e", Some(10)).is_err());
+    }
+
+    #[test]
+    fn unknown_component() {
+        assert!(diamond().paths("r", "unknown", Some(10)).is_err());
+        assert!(diamond().paths("unknown", "x", Some(10)).is_err());
+    }
+
+    #[test]
+    fn same_as() {
+        let sample = |app: &str, lib: &str| {
+            let mut graph = graph([
+                with(
+                    node(app, "app"),
+                    [with_purls(app, "lib", &["pkg:npm/lib@1"])],
+                ),
+                with(
+                    with_purls(lib, "lib", &["pkg:npm/lib@1"]),
+                    [node(lib, "vuln")],
+                ),
+            ]);
+            graph.link(&Link {
+                same_as: vec![Identity::Purl],
+                ..Default::default()
+            });
+            graph
+        };
+
+        // the link may point either way, depending on the order of the SBOMs
+        for (app, lib) in [("s1", "s2"), ("s2", "s1")] {
+            let mut graph = sample(app, lib);
+            let paths = graph.paths("app", "vuln", None).unwrap();
+            assert_eq!(
+                paths,
+                vec![vec![
+                    key(app, "app"),
+                    key(app, "lib"),
+                    key(lib, "lib"),
+                    key(lib, "vuln")
+                ]]
+            );
+
+            let mut output = vec![];
+            graph.render_paths(&paths, &mut output).unwrap();
+            assert_eq!(
+                String::from_utf8(output).unwrap(),
+                format!(
+                    "app ({app}--app) -[dependency_of]-> lib ({app}--lib) -[same_as]-> lib ({lib}--lib) -[dependency_of]-> vuln ({lib}--vuln)\n"
+                )
+            );
+
+            graph.retain_paths(&paths);
+            assert_eq!(graph.relationships.len(), 3);
+        }
+    }
+
+    #[test]
+    fn cycle() {
+        // a -> b -> c -> a, b -> d
+        let graph = graph([
+            with(node("s", "a"), [node("s", "b")]),
+            with(node("s", "b"), [node("s", "c"), node("s", "d")]),
+            with(node("s", "c"), [node("s", "a")]),
+        ]);
+
+        let paths = graph.paths("a", "d", Some(10)).unwrap();
+        assert_eq!(keys(paths), vec![vec!["a", "b", "d"]]);
+
+        let paths = graph.paths("c", "b", Some(10)).unwrap();
+        assert_eq!(keys(paths), vec![vec!["c", "a", "b"]]);
+    }
+
+    #[test]
+    fn many() {
+        // 8 layers of 4 components, each depending on all of the next layer
+        let layer = |i: usize| (0..4).map(move |j| format!("{i}-{j}"));
+        let mut items = vec![with(node("s", "root"), layer(0).map(|id| node("s", &id)))];
+        for i in 0..7 {
+            for id in layer(i) {
+                items.push(with(node("s", &id), layer(i + 1).map(|id| node("s", &id))));
+            }
+        }
+        for id in layer(7) {
+            items.push(with(node("s", &id), [node("s", "leaf")]));
+        }
+
+        let paths = graph(items).paths("root", "leaf", Some(100)).unwrap();
+        assert_eq!(paths.len(), 100);
+        assert!(paths.iter().all(|path| path.len() == 10));
+    }
+}
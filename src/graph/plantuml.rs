@@ -0,0 +1,95 @@
+use super::Graph;
+use crate::model::Key;
+use std::collections::BTreeMap;
+
+/// Make a string safe for use in a quoted PlantUML name, or as label
+fn encode(s: impl AsRef<str>) -> String {
+    s.as_ref().replace('"', "'").replace(['\r', '\n'], " ")
+}
+
+impl Graph {
+    pub fn render_plantuml<W>(self, w: &mut W) -> Result<(), std::io::Error>
+    where
+        W: std::io::Write,
+    {
+        writeln!(w, "@startuml")?;
+        writeln!(w, "left to right direction")?;
+
+        // names may be ambiguous, so we use generated aliases, in a stable order
+        let mut ids = BTreeMap::<Key, String>::new();
+
+        for (n, (sbom, nodes)) in self.sorted_nodes().into_iter().enumerate() {
+            let sbom_doc = self.sboms.get(sbom).expect("SBOM not found");
+
+            writeln!(
+                w,
+                r#"package "{label}" as s{n} {{"#,
+                label = encode(format!(
+                    "{} / {} / {}",
+                    sbom_doc.document_id, sbom_doc.published, sbom
+                ))
+            )?;
+
+            for node in nodes {
+                let id = format!("n{}", ids.len());
+                writeln!(
+                    w,
+                    r#"  component "{label}" as {id}"#,
+                    label = encode(&node.name)
+                )?;
+                ids.insert(node.as_key(), id);
+            }
+
+            writeln!(w, "}}")?;
+        }
+
+        for ((from, to), rel) in self.sorted_relationships() {
+            let (Some(from), Some(to)) = (ids.get(from), ids.get(to)) else {
+                continue;
+            };
+
+            writeln!(w, "{from} --> {to} : {label}", label = encode(rel))?;
+        }
+
+        writeln!(w, "@enduml")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::test::{graph, node, with};
+
+    #[test]
+    fn encoding() {
+        assert_eq!(encode("a \"b\"\r\nc"), "a 'b'  c");
+    }
+
+    #[test]
+    fn render() {
+        let mut b = node("s1", "b");
+        b.name = r#"lib "b""#.into();
+        let mut output = vec![];
+        graph([with(node("s1", "a"), [b]), node("s2", "x")])
+            .render_plantuml(&mut output)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"@startuml
+left to right direction
+package "doc-s1 / 2025-01-01T00:00:00Z / s1" as s0 {
+  component "a" as n0
+  component "lib 'b'" as n1
+}
+package "doc-s2 / 2025-01-01T00:00:00Z / s2" as s1 {
+  component "x" as n2
+}
+n0 --> n1 : dependency_of
+@enduml
+"#
+        );
+    }
+}
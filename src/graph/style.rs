@@ -0,0 +1,657 @@
+use super::template::Template;
+use super::{Graph, purls};
+use crate::model::{Key, Node};
+use anyhow::{Context, bail};
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Colors, used in a round-robin fashion
+const COLORS: &[Color] = &[
+    Color(0x4e, 0x79, 0xa7),
+    Color(0xf2, 0x8e, 0x2b),
+    Color(0xe1, 0x57, 0x59),
+    Color(0x76, 0xb7, 0xb2),
+    Color(0x59, 0xa1, 0x4f),
+    Color(0xed, 0xc9, 0x48),
+    Color(0xb0, 0x7a, 0xa1),
+    Color(0xff, 0x9d, 0xa7),
+    Color(0x9c, 0x75, 0x5f),
+    Color(0xba, 0xb0, 0xac),
+];
+
+/// Shapes, used in a round-robin fashion
+const SHAPES: &[Shape] = &[
+    Shape::Box,
+    Shape::Ellipse,
+    Shape::Diamond,
+    Shape::Triangle,
+    Shape::Hexagon,
+    Shape::Octagon,
+];
+
+/// The category of components without a PURL
+const UNKNOWN: &str = "unknown";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Display for Color {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+impl FromStr for Color {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(hex) = s
+            .strip_prefix('#')
+            .filter(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        else {
+            bail!("invalid color '{s}', must be in the form of '#rrggbb'");
+        };
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+
+        Ok(Self(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Shape {
+    Box,
+    Ellipse,
+    Diamond,
+    Triangle,
+    Hexagon,
+    Octagon,
+}
+
+impl Shape {
+    pub fn dot(&self) -> &'static str {
+        match self {
+            Self::Box => "box",
+            Self::Ellipse => "ellipse",
+            Self::Diamond => "diamond",
+            Self::Triangle => "triangle",
+            Self::Hexagon => "hexagon",
+            Self::Octagon => "octagon",
+        }
+    }
+
+    /// GEXF only knows a few shapes, falling back to a disc
+    pub fn gexf(&self) -> &'static str {
+        match self {
+            Self::Box => "square",
+            Self::Diamond => "diamond",
+            Self::Triangle => "triangle",
+            Self::Ellipse | Self::Hexagon | Self::Octagon => "disc",
+        }
+    }
+
+    pub fn yed(&self) -> &'static str {
+        match self {
+            Self::Box => "rectangle",
+            Self::Ellipse => "ellipse",
+            Self::Diamond => "diamond",
+            Self::Triangle => "triangle",
+            Self::Hexagon => "hexagon",
+            Self::Octagon => "octagon",
+        }
+    }
+}
+
+/// What to style components by
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum StyleBy {
+    /// The type of their (first) PURL, like `maven` or `npm`
+    PurlType,
+    /// The SBOM they belong to
+    Sbom,
+}
+
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct Style {
+    /// Color and shape components by their PURL type or SBOM
+    #[arg(long, global = true, value_enum)]
+    pub style_by: Option<StyleBy>,
+
+    /// Color relationships by their type
+    #[arg(long, global = true)]
+    pub color_relationships: bool,
+
+    /// A TOML file with rules, styling matching components and relationships
+    #[arg(long = "style", global = true, value_name = "FILE", value_parser = StyleFile::parse)]
+    pub file: Option<StyleFile>,
+
+    /// A template for the labels of components, like `{name}@{purl.version}`.
+    ///
+    /// The keywords `node-id`, `name`, `purl`, `purl-type`, `purl-name` and `purl-version` are
+    /// accepted too, falling back to the name if there is no PURL.
+    #[arg(short, long, global = true, value_name = "TEMPLATE")]
+    pub label: Option<Template>,
+}
+
+/// A regular expression, matching a component name
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct NamePattern(Regex);
+
+impl TryFrom<String> for NamePattern {
+    type Error = regex::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Ok(Self(Regex::new(&value)?))
+    }
+}
+
+/// A rule, styling the components it matches
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct NodeRule {
+    /// Match components having a PURL of this type
+    pub purl_type: Option<String>,
+    /// Match components with a name matching this regular expression
+    pub name: Option<NamePattern>,
+    /// Match components of this SBOM
+    pub sbom: Option<String>,
+
+    #[serde(flatten)]
+    pub style: NodeStyle,
+}
+
+impl NodeRule {
+    fn matches(&self, node: &Node) -> bool {
+        self.purl_type
+            .as_ref()
+            .is_none_or(|purl_type| purls(node).iter().any(|purl| &purl.r#type == purl_type))
+            && self
+                .name
+                .as_ref()
+                .is_none_or(|NamePattern(name)| name.is_match(&node.name))
+            && self.sbom.as_ref().is_none_or(|sbom| sbom == &node.sbom_id)
+    }
+}
+
+/// A rule, styling the relationships it matches
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct EdgeRule {
+    /// Match relationships of this type
+    pub relationship: Option<String>,
+    /// Match relationships starting in this SBOM
+    pub sbom: Option<String>,
+
+    #[serde(flatten)]
+    pub style: EdgeStyle,
+}
+
+impl EdgeRule {
+    fn matches(&self, from: &Key, relationship: &str) -> bool {
+        self.relationship
+            .as_ref()
+            .is_none_or(|rel| rel == relationship)
+            && self.sbom.as_ref().is_none_or(|sbom| sbom == &from.sbom)
+    }
+}
+
+/// The style configuration file.
+///
+/// Rules are applied in order, later rules overriding the properties of earlier ones.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StyleFile {
+    #[serde(default, rename = "node")]
+    pub nodes: Vec<NodeRule>,
+    #[serde(default, rename = "edge")]
+    pub edges: Vec<EdgeRule>,
+}
+
+impl StyleFile {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read style file: {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("failed to parse style file: {}", path.display()))
+    }
+
+    fn parse(path: &str) -> Result<Self, String> {
+        Self::load(Path::new(path)).map_err(|err| format!("{err:#}"))
+    }
+}
+
+/// The visual properties of a component, all optional
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct NodeStyle {
+    pub color: Option<Color>,
+    pub shape: Option<Shape>,
+    pub pen_width: Option<f32>,
+    #[serde(default, deserialize_with = "template")]
+    pub label: Option<Template>,
+}
+
+impl NodeStyle {
+    fn apply(&mut self, other: &NodeStyle) {
+        self.color = other.color.or(self.color);
+        self.shape = other.shape.or(self.shape);
+        self.pen_width = other.pen_width.or(self.pen_width);
+        self.label = other.label.clone().or(self.label.take());
+    }
+}
+
+/// The visual properties of a relationship, all optional
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct EdgeStyle {
+    pub color: Option<Color>,
+    pub pen_width: Option<f32>,
+    #[serde(default, deserialize_with = "template")]
+    pub label: Option<Template>,
+}
+
+impl EdgeStyle {
+    fn apply(&mut self, other: &EdgeStyle) {
+        self.color = other.color.or(self.color);
+        self.pen_width = other.pen_width.or(self.pen_width);
+        self.label = other.label.clone().or(self.label.take());
+    }
+}
+
+fn template<'de, D>(deserializer: D) -> Result<Option<Template>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = <Option<String> as serde::Deserialize>::deserialize(deserializer)?;
+    value
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// The styles assigned to the components and relationships of a graph
+#[derive(Clone, Debug, Default)]
+pub struct Styles {
+    by: Option<StyleBy>,
+    /// Colors and shapes of the categories of components
+    pub categories: BTreeMap<String, (Color, Shape)>,
+    /// Colors of the relationships
+    pub relationships: BTreeMap<String, Color>,
+    label: Option<Template>,
+    file: StyleFile,
+}
+
+impl Styles {
+    fn category(by: StyleBy, node: &Node) -> String {
+        match by {
+            StyleBy::PurlType => purls(node)
+                .into_iter()
+                .next()
+                .map(|purl| purl.r#type)
+                .unwrap_or_else(|| UNKNOWN.to_string()),
+            StyleBy::Sbom => node.sbom_id.clone(),
+        }
+    }
+
+    /// The style of a component, from its category, the label template and the matching rules
+    pub fn node(&self, node: &Node) -> NodeStyle {
+        let mut result = NodeStyle {
+            label: self.label.clone(),
+            ..Default::default()
+        };
+
+        if let Some(by) = self.by
+            && let Some((color, shape)) = self.categories.get(&Self::category(by, node))
+        {
+            result.color = Some(*color);
+            result.shape = Some(*shape);
+        }
+
+        for rule in &self.file.nodes {
+            if rule.matches(node) {
+                result.apply(&rule.style);
+            }
+        }
+
+        result
+    }
+
+    /// The style of a relationship, from its type and the matching rules
+    pub fn edge(&self, from: &Key, relationship: &str) -> EdgeStyle {
+        let mut result = EdgeStyle {
+            color: self.relationships.get(relationship).copied(),
+            ..Default::default()
+        };
+
+        for rule in &self.file.edges {
+            if rule.matches(from, relationship) {
+                result.apply(&rule.style);
+            }
+        }
+
+        result
+    }
+
+    /// Check if there is anything to show in a legend
+    pub fn has_legend(&self) -> bool {
+        !self.categories.is_empty() || !self.relationships.is_empty()
+    }
+
+    /// The legend of the component categories, like `maven: #4e79a7 box`
+    pub fn node_legend(&self) -> Vec<String> {
+        self.categories
+            .iter()
+            .map(|(category, (color, shape))| format!("{category}: {color} {}", shape.dot()))
+            .collect()
+    }
+
+    /// The legend of the relationships, like `dependency_of: #4e79a7`
+    pub fn edge_legend(&self) -> Vec<String> {
+        self.relationships
+            .iter()
+            .map(|(rel, color)| format!("{rel}: {color}"))
+            .collect()
+    }
+}
+
+impl Graph {
+    /// Assign styles to the categories of components and relationships.
+    pub fn styles(&self, style: &Style) -> Styles {
+        let mut result = Styles {
+            by: style.style_by,
+            label: style.label.clone(),
+            file: style.file.clone().unwrap_or_default(),
+            ..Default::default()
+        };
+
+        if let Some(by) = style.style_by {
+            let categories = self
+                .nodes
+                .values()
+                .flat_map(|nodes| nodes.values())
+                .map(|node| Styles::category(by, node))
+                .collect::<BTreeSet<_>>();
+
+            result.categories = categories
+                .into_iter()
+                .enumerate()
+                .map(|(i, category)| {
+                    let style = (COLORS[i % COLORS.len()], SHAPES[i % SHAPES.len()]);
+                    (category, style)
+                })
+                .collect();
+        }
+
+        if style.color_relationships {
+            let relationships = self.relationships.values().collect::<BTreeSet<_>>();
+
+            result.relationships = relationships
+                .into_iter()
+                .enumerate()
+                .map(|(i, rel)| (rel.clone(), COLORS[i % COLORS.len()]))
+                .collect();
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::test::{graph, key, node, with, with_purls};
+
+    /// Components of three PURL types, and one without a PURL
+    fn sample() -> Graph {
+        graph([
+            with(
+                with_purls("s1", "app", &["pkg:maven/org.ex/app@1"]),
+                [
+                    with_purls("s1", "lib", &["pkg:maven/org.ex/lib@1"]),
+                    with_purls("s1", "dep", &["pkg:npm/dep@1", "pkg:maven/org.ex/dep@1"]),
+                ],
+            ),
+            with(
+                with_purls("s2", "rpm", &["pkg:rpm/redhat/rpm@1"]),
+                [node("s2", "plain")],
+            ),
+        ])
+    }
+
+    fn style_by(by: StyleBy) -> Style {
+        Style {
+            style_by: Some(by),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn categories() {
+        let graph = sample();
+        let styles = graph.styles(&style_by(StyleBy::PurlType));
+
+        assert_eq!(
+            styles.categories,
+            BTreeMap::from([
+                ("maven".into(), (COLORS[0], SHAPES[0])),
+                ("npm".into(), (COLORS[1], SHAPES[1])),
+                ("rpm".into(), (COLORS[2], SHAPES[2])),
+                (UNKNOWN.into(), (COLORS[3], SHAPES[3])),
+            ])
+        );
+        assert!(styles.relationships.is_empty());
+
+        // the first PURL decides
+        let dep = graph.node(&key("s1", "dep")).unwrap();
+        assert_eq!(styles.node(dep).color, Some(COLORS[1]));
+        let plain = graph.node(&key("s2", "plain")).unwrap();
+        assert_eq!(styles.node(plain).shape, Some(SHAPES[3]));
+
+        let styles = graph.styles(&style_by(StyleBy::Sbom));
+        assert_eq!(
+            styles.categories.keys().collect::<Vec<_>>(),
+            vec!["s1", "s2"]
+        );
+    }
+
+    #[test]
+    fn round_robin() {
+        let graph = graph((0..12).map(|i| node(&format!("s{i:02}"), "a")));
+        let styles = graph.styles(&style_by(StyleBy::Sbom));
+
+        assert_eq!(styles.categories["s00"], (COLORS[0], SHAPES[0]));
+        assert_eq!(styles.categories["s06"], (COLORS[6], SHAPES[0]));
+        assert_eq!(styles.categories["s10"], (COLORS[0], SHAPES[4]));
+        assert_eq!(styles.categories["s11"], (COLORS[1], SHAPES[5]));
+    }
+
+    #[test]
+    fn unstyled() {
+        let graph = sample();
+        let styles = graph.styles(&Style::default());
+
+        assert!(!styles.has_legend());
+        let app = graph.node(&key("s1", "app")).unwrap();
+        let style = styles.node(app);
+        assert_eq!((style.color, style.shape), (None, None));
+        assert_eq!(styles.edge(&key("s1", "app"), "dependency_of").color, None);
+    }
+
+    #[test]
+    fn relationships() {
+        let mut graph = sample();
+        graph
+            .relationships
+            .insert((key("s1", "lib"), key("s1", "dep")), "variant_of".into());
+        let styles = graph.styles(&Style {
+            color_relationships: true,
+            ..Default::default()
+        });
+
+        assert!(styles.categories.is_empty());
+        assert_eq!(
+            styles.edge(&key("s1", "app"), "dependency_of").color,
+            Some(COLORS[0])
+        );
+        assert_eq!(
+            styles.edge(&key("s1", "lib"), "variant_of").color,
+            Some(COLORS[1])
+        );
+    }
+
+    #[test]
+    fn legend() {
+        let graph = sample();
+        let styles = graph.styles(&Style {
+            style_by: Some(StyleBy::PurlType),
+            color_relationships: true,
+            ..Default::default()
+        });
+
+        assert!(styles.has_legend());
+        assert_eq!(
+            styles.node_legend(),
+            vec![
+                "maven: #4e79a7 box",
+                "npm: #f28e2b ellipse",
+                "rpm: #e15759 diamond",
+                "unknown: #76b7b2 triangle",
+            ]
+        );
+        assert_eq!(styles.edge_legend(), vec!["dependency_of: #4e79a7"]);
+    }
+
+    /// Rules for all maven components, and some of the npm ones
+    const STYLE: &str = r##"
+[[node]]
+purl-type = "maven"
+color = "#111111"
+shape = "hexagon"
+
+[[node]]
+name = "^l"
+color = "#222222"
+pen-width = 2
+
+[[node]]
+purl-type = "npm"
+sbom = "s2"
+color = "#333333"
+
+[[edge]]
+relationship = "dependency_of"
+color = "#444444"
+
+[[edge]]
+sbom = "s2"
+pen-width = 3
+label = "{{{relationship}}}"
+"##;
+
+    fn style_file(content: &str) -> anyhow::Result<StyleFile> {
+        Ok(toml::from_str(content)?)
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!("#4e79a7".parse::<Color>().unwrap(), Color(0x4e, 0x79, 0xa7));
+        assert_eq!("#FFFFFF".parse::<Color>().unwrap().to_string(), "#ffffff");
+        for color in [
+            "red", "4e79a7", "#4e79a", "#4e79a7f", "#gg0000", "#+1+1+1", "#€000",
+        ] {
+            assert!(color.parse::<Color>().is_err(), "{color}");
+        }
+    }
+
+    #[test]
+    fn rules() {
+        let graph = graph([
+            with(
+                with_purls("s1", "app", &["pkg:maven/org.ex/app@1"]),
+                [
+                    with_purls("s1", "lib", &["pkg:maven/org.ex/lib@1"]),
+                    with_purls("s1", "dep", &["pkg:npm/dep@1"]),
+                ],
+            ),
+            with(
+                with_purls("s2", "dep", &["pkg:npm/dep@1"]),
+                [node("s2", "lone")],
+            ),
+        ]);
+        let styles = graph.styles(&Style {
+            file: Some(style_file(STYLE).unwrap()),
+            ..Default::default()
+        });
+        let node = |sbom, id| styles.node(graph.node(&key(sbom, id)).unwrap());
+
+        let app = node("s1", "app");
+        assert_eq!(app.color, Some(Color(0x11, 0x11, 0x11)));
+        assert_eq!(app.shape, Some(Shape::Hexagon));
+        assert_eq!(app.pen_width, None);
+
+        // later rules override earlier ones, but only the properties they set
+        let lib = node("s1", "lib");
+        assert_eq!(lib.color, Some(Color(0x22, 0x22, 0x22)));
+        assert_eq!(lib.shape, Some(Shape::Hexagon));
+        assert_eq!(lib.pen_width, Some(2.0));
+        assert_eq!(node("s2", "lone").color, Some(Color(0x22, 0x22, 0x22)));
+
+        // all conditions of a rule must match
+        assert_eq!(node("s1", "dep").color, None);
+        assert_eq!(node("s2", "dep").color, Some(Color(0x33, 0x33, 0x33)));
+
+        let edge = styles.edge(&key("s1", "app"), "dependency_of");
+        assert_eq!(edge.color, Some(Color(0x44, 0x44, 0x44)));
+        assert_eq!(edge.pen_width, None);
+        let edge = styles.edge(&key("s2", "dep"), "dependency_of");
+        assert_eq!(edge.color, Some(Color(0x44, 0x44, 0x44)));
+        assert_eq!(edge.pen_width, Some(3.0));
+        assert_eq!(edge.label.unwrap().edge("dependency_of"), "{dependency_of}");
+        assert_eq!(styles.edge(&key("s1", "app"), "variant_of").color, None);
+    }
+
+    #[test]
+    fn invalid_rules() {
+        for content in [
+            "[[node]]\ncolor = \"red\"",
+            "[[node]]\ncolor = \"#12345\"",
+            "[[node]]\nshape = \"circle\"",
+            "[[node]]\ncolour = \"#ff0000\"",
+            "[[node]]\npen_width = 2",
+            "[[node]]\nname = \"(\"",
+            "[[node]]\nlabel = \"{nope}\"",
+            "[[edge]]\nshape = \"box\"",
+            "[[nodes]]\ncolor = \"#ff0000\"",
+        ] {
+            assert!(style_file(content).is_err(), "{content}");
+        }
+    }
+
+    #[test]
+    fn load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("style.toml");
+
+        assert!(StyleFile::load(&path).is_err());
+
+        std::fs::write(&path, STYLE).unwrap();
+        let file = StyleFile::load(&path).unwrap();
+        assert_eq!((file.nodes.len(), file.edges.len()), (3, 2));
+
+        std::fs::write(&path, "[[node]]\ncolor = 1").unwrap();
+        let err = StyleFile::load(&path).unwrap_err();
+        assert!(format!("{err:#}").starts_with("failed to parse style file"));
+    }
+}
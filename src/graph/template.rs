@@ -0,0 +1,309 @@
+use super::{cpes, purls};
+use crate::cpe::Cpe;
+use crate::model::Node;
+use crate::purl::Purl;
+use anyhow::{anyhow, bail};
+use std::str::FromStr;
+
+/// A field which can be used in a template
+#[derive(Copy, Clone, Debug, PartialEq, Eq, strum::EnumString, strum::VariantNames)]
+#[strum(serialize_all = "snake_case")]
+enum Field {
+    Name,
+    NodeId,
+    SbomId,
+    DocumentId,
+    ProductName,
+    ProductVersion,
+    Purl,
+    #[strum(serialize = "purl.type")]
+    PurlType,
+    #[strum(serialize = "purl.namespace")]
+    PurlNamespace,
+    #[strum(serialize = "purl.name")]
+    PurlName,
+    #[strum(serialize = "purl.version")]
+    PurlVersion,
+    /// The namespace and name
+    #[strum(serialize = "purl.qualified_name")]
+    PurlQualifiedName,
+    /// The namespace, name and version
+    #[strum(serialize = "purl.qualified_version")]
+    PurlQualifiedVersion,
+    Cpe,
+    #[strum(serialize = "cpe.vendor")]
+    CpeVendor,
+    #[strum(serialize = "cpe.product")]
+    CpeProduct,
+    #[strum(serialize = "cpe.version")]
+    CpeVersion,
+    Relationship,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    /// A field, along with its alternatives
+    Field(Vec<Field>),
+}
+
+/// Keywords of the former fixed labels, as aliases of the equivalent templates
+const ALIASES: &[(&str, &str)] = &[
+    ("node-id", "{node_id}"),
+    ("name", "{name}"),
+    ("purl", "{purl|name}"),
+    ("purl-type", "{purl.type|name}"),
+    ("purl-name", "{purl.qualified_name|name}"),
+    ("purl-version", "{purl.qualified_version|name}"),
+];
+
+/// A label template, like `{name}@{purl.version}`.
+///
+/// Fields are replaced with the (first) value of a component or relationship, or nothing if
+/// missing. A field may list alternatives, like `{purl.type|name}`, using the first one present.
+/// Use `{{` and `}}` for literal braces.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template(Vec<Part>);
+
+impl FromStr for Template {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((_, template)) = ALIASES.iter().find(|(alias, _)| *alias == s) {
+            return template.parse();
+        }
+
+        let mut parts = vec![];
+        let mut text = String::new();
+        let mut chars = s.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => bail!("unterminated field '{{{name}'"),
+                        }
+                    }
+                    let fields = name
+                        .split('|')
+                        .map(|name| {
+                            Field::from_str(name.trim()).map_err(|_| {
+                                anyhow!(
+                                    "unknown field '{name}', must be one of: {}",
+                                    <Field as strum::VariantNames>::VARIANTS.join(", ")
+                                )
+                            })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Field(fields));
+                }
+                '}' => bail!("unmatched '}}', use '}}}}' for a literal brace"),
+                c => text.push(c),
+            }
+        }
+
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(Self(parts))
+    }
+}
+
+impl Template {
+    /// Render the label of a component
+    pub fn node(&self, node: &Node) -> String {
+        self.render(Some(node), None)
+    }
+
+    /// Render the label of a relationship
+    pub fn edge(&self, relationship: &str) -> String {
+        self.render(None, Some(relationship))
+    }
+
+    fn render(&self, node: Option<&Node>, relationship: Option<&str>) -> String {
+        let purl = node.and_then(|node| purls(node).into_iter().next());
+        let cpe = node.and_then(|node| cpes(node).into_iter().next());
+
+        let mut result = String::new();
+        for part in &self.0 {
+            match part {
+                Part::Text(text) => result.push_str(text),
+                Part::Field(fields) => {
+                    let value = fields
+                        .iter()
+                        .filter_map(|field| {
+                            field.value(node, relationship, purl.as_ref(), cpe.as_ref())
+                        })
+                        .find(|value| !value.is_empty());
+                    result.push_str(&value.unwrap_or_default());
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl Field {
+    fn value(
+        &self,
+        node: Option<&Node>,
+        relationship: Option<&str>,
+        purl: Option<&Purl>,
+        cpe: Option<&Cpe>,
+    ) -> Option<String> {
+        match self {
+            Self::Name => node.map(|node| node.name.clone()),
+            Self::NodeId => node.map(|node| node.node_id.clone()),
+            Self::SbomId => node.map(|node| node.sbom_id.clone()),
+            Self::DocumentId => node.map(|node| node.document_id.clone()),
+            Self::ProductName => node.and_then(|node| node.product_name.clone()),
+            Self::ProductVersion => node.and_then(|node| node.product_version.clone()),
+            Self::Purl => node.and_then(|node| node.purl.first().cloned()),
+            Self::PurlType => purl.map(|purl| purl.r#type.clone()),
+            Self::PurlNamespace => purl.and_then(|purl| purl.namespace.clone()),
+            Self::PurlName => purl.map(|purl| purl.name.clone()),
+            Self::PurlVersion => purl.and_then(|purl| purl.version.clone()),
+            Self::PurlQualifiedName => purl.map(Purl::qualified_name),
+            Self::PurlQualifiedVersion => purl.map(|purl| match &purl.version {
+                Some(version) => format!("{}@{version}", purl.qualified_name()),
+                None => purl.qualified_name(),
+            }),
+            Self::Cpe => node.and_then(|node| node.cpe.first().cloned()),
+            Self::CpeVendor => cpe.and_then(|cpe| cpe.vendor.clone()),
+            Self::CpeProduct => cpe.and_then(|cpe| cpe.product.clone()),
+            Self::CpeVersion => cpe.and_then(|cpe| cpe.version.clone()),
+            Self::Relationship => relationship.map(ToString::to_string),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::test::node;
+
+    fn component() -> Node {
+        let mut node = node("s1", "SPDXRef-lib");
+        node.name = "lib".into();
+        node.purl = vec![
+            "pkg:maven/org.ex/lib@1.0?type=jar".into(),
+            "pkg:npm/lib@2.0".into(),
+        ];
+        node.cpe = vec!["cpe:2.3:a:ex:lib:1.0:*:*:*:*:*:*:*".into()];
+        node.product_name = Some("product".into());
+        node
+    }
+
+    fn render(template: &str) -> String {
+        template.parse::<Template>().unwrap().node(&component())
+    }
+
+    #[test]
+    fn fields() {
+        assert_eq!(render("{name}@{purl.version}"), "lib@1.0");
+        assert_eq!(
+            render("{sbom_id}/{node_id} ({document_id})"),
+            "s1/SPDXRef-lib (doc-s1)"
+        );
+        assert_eq!(
+            render("{purl.type}:{purl.namespace}/{purl.name}"),
+            "maven:org.ex/lib"
+        );
+        assert_eq!(render("{purl}"), "pkg:maven/org.ex/lib@1.0?type=jar");
+        assert_eq!(
+            render("{cpe.vendor} {cpe.product} {cpe.version}"),
+            "ex lib 1.0"
+        );
+        assert_eq!(render("{ name }"), "lib");
+    }
+
+    #[test]
+    fn qualified() {
+        assert_eq!(render("{purl.qualified_name}"), "org.ex/lib");
+        assert_eq!(render("{purl.qualified_version}"), "org.ex/lib@1.0");
+
+        let mut node = component();
+        node.purl = vec!["pkg:npm/lib".into()];
+        let template = "{purl.qualified_name} {purl.qualified_version}"
+            .parse::<Template>()
+            .unwrap();
+        assert_eq!(template.node(&node), "lib lib");
+    }
+
+    #[test]
+    fn alternatives() {
+        assert_eq!(render("{product_version|product_name}"), "product");
+        assert_eq!(render("{product_version | purl.version|name}"), "1.0");
+        assert_eq!(render("{product_version|relationship}"), "");
+        assert!("{name|nme}".parse::<Template>().is_err());
+    }
+
+    #[test]
+    fn aliases() {
+        let mut plain = component();
+        plain.purl = vec![];
+
+        for (alias, expected, fallback) in [
+            ("node-id", "SPDXRef-lib", "SPDXRef-lib"),
+            ("name", "lib", "lib"),
+            ("purl", "pkg:maven/org.ex/lib@1.0?type=jar", "lib"),
+            ("purl-type", "maven", "lib"),
+            ("purl-name", "org.ex/lib", "lib"),
+            ("purl-version", "org.ex/lib@1.0", "lib"),
+        ] {
+            let template = alias.parse::<Template>().unwrap();
+            assert_eq!(template.node(&component()), expected, "{alias}");
+            assert_eq!(template.node(&plain), fallback, "{alias}");
+        }
+
+        assert_eq!(render("names"), "names");
+    }
+
+    #[test]
+    fn missing() {
+        assert_eq!(render("{product_name} {product_version}"), "product ");
+        assert_eq!(render("[{relationship}]"), "[]");
+    }
+
+    #[test]
+    fn edge() {
+        let template = "<{relationship}{name}>".parse::<Template>().unwrap();
+        assert_eq!(template.edge("dependency_of"), "<dependency_of>");
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(render("{{{name}}}"), "{lib}");
+        assert_eq!(render("}}{{"), "}{");
+        assert_eq!(render("plain"), "plain");
+        assert_eq!(render(""), "");
+    }
+
+    #[test]
+    fn invalid() {
+        let err = "{nme}".parse::<Template>().unwrap_err().to_string();
+        assert!(err.contains("unknown field 'nme'"), "{err}");
+        assert!(err.contains("purl.version"), "{err}");
+
+        assert!("{name".parse::<Template>().is_err());
+        assert!("name}".parse::<Template>().is_err());
+    }
+}
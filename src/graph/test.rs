@@ -0,0 +1,162 @@
+//! Helpers for building graphs in tests, along with tests of the graph module itself.
+
+use super::style::StyleBy;
+use super::{Format, Graph, Style};
+use crate::input::Input;
+use crate::model::{Key, Node, PaginatedResult};
+
+/// A component named after its ID, without any relationships.
+pub fn node(sbom: &str, id: &str) -> Node {
+    Node {
+        sbom_id: sbom.into(),
+        node_id: id.into(),
+        purl: vec![],
+        cpe: vec![],
+        name: id.into(),
+        published: "2025-01-01T00:00:00Z".into(),
+        document_id: format!("doc-{sbom}"),
+        product_name: None,
+        product_version: None,
+        relationship: None,
+        ancestors: vec![],
+        descendants: vec![],
+    }
+}
+
+/// A component having the given PURLs.
+pub fn with_purls(sbom: &str, id: &str, purls: &[&str]) -> Node {
+    let mut node = node(sbom, id);
+    node.purl = purls.iter().map(ToString::to_string).collect();
+    node
+}
+
+/// The key of a component.
+pub fn key(sbom: &str, node: &str) -> Key {
+    Key {
+        sbom: sbom.into(),
+        node: node.into(),
+    }
+}
+
+/// Add descendants to a component, related by `dependency_of`.
+pub fn with(mut node: Node, descendants: impl IntoIterator<Item = Node>) -> Node {
+    node.descendants
+        .extend(descendants.into_iter().map(|mut descendant| {
+            descendant.relationship = Some("dependency_of".into());
+            descendant
+        }));
+    node
+}
+
+/// A graph of the given top-level items.
+pub fn graph(items: impl IntoIterator<Item = Node>) -> Graph {
+    let items = items.into_iter().collect::<Vec<_>>();
+    Graph::new(&[Input {
+        source: "test.json".into(),
+        result: PaginatedResult {
+            total: items.len(),
+            items,
+        },
+    }])
+}
+
+/// The keys of all components of a graph, sorted.
+pub fn keys(graph: &Graph) -> Vec<String> {
+    let mut result = graph
+        .nodes
+        .values()
+        .flat_map(|nodes| nodes.values())
+        .map(|node| node.as_key().to_string())
+        .collect::<Vec<_>>();
+    result.sort();
+    result
+}
+
+/// A graph of a few components across SBOMs, large enough for the order of iterating over it
+/// to vary.
+pub fn sample() -> Graph {
+    graph([
+        with(
+            node("s1", "a"),
+            [with(node("s1", "b"), [node("s1", "c"), node("s1", "d")])],
+        ),
+        with(node("s2", "x"), [node("s2", "y"), node("s2", "z")]),
+        with(node("s3", "m"), [node("s3", "n")]),
+    ])
+}
+
+/// Render the sample graph, for comparing the output of multiple runs.
+pub fn render(f: impl FnOnce(Graph, &mut Vec<u8>) -> anyhow::Result<()>) -> String {
+    let mut result = vec![];
+    f(sample(), &mut result).unwrap();
+    String::from_utf8(result).unwrap()
+}
+
+/// A maven component depending on one without a PURL, styled by PURL type. The maven one gets
+/// the first color and shape (`#4e79a7`, box), the other one the second ones (`#f28e2b`,
+/// ellipse).
+pub fn styled() -> (Graph, Style) {
+    let graph = graph([with(
+        with_purls("s1", "a", &["pkg:maven/org.ex/a@1"]),
+        [node("s1", "b")],
+    )]);
+    let style = Style {
+        style_by: Some(StyleBy::PurlType),
+        color_relationships: true,
+        ..Default::default()
+    };
+    (graph, style)
+}
+
+/// The part of an XML document describing a node.
+pub fn xml_node<'a>(xml: &'a str, id: &str) -> &'a str {
+    let start = xml.find(&format!(r#"<node id="{id}""#)).unwrap();
+    let end = start + xml[start..].find("</node>").unwrap();
+    &xml[start..end]
+}
+
+#[test]
+fn sources() {
+    let input = |source: &str, items| Input {
+        source: source.into(),
+        result: PaginatedResult { items, total: 2 },
+    };
+    let graph = Graph::new(&[
+        input("a.json", vec![with(node("s1", "a"), [node("s1", "b")])]),
+        input(
+            "b.json",
+            vec![with(node("s1", "a"), [node("s1", "c")]), node("s2", "x")],
+        ),
+    ]);
+
+    let sources = |sbom, id| graph.node_sources(&key(sbom, id)).collect::<Vec<_>>();
+    assert_eq!(sources("s1", "a"), vec!["a.json", "b.json"]);
+    assert_eq!(sources("s1", "b"), vec!["a.json"]);
+    assert_eq!(sources("s2", "x"), vec!["b.json"]);
+    assert_eq!(
+        graph
+            .relationship_sources(&key("s1", "a"), &key("s1", "c"))
+            .collect::<Vec<_>>(),
+        vec!["b.json"]
+    );
+    assert_eq!(keys(&graph), vec!["s1--a", "s1--b", "s1--c", "s2--x"]);
+    assert_eq!(graph.roots.len(), 2);
+}
+
+#[test]
+fn format_from_path() {
+    let format = |path: &str| Format::from_path(std::path::Path::new(path));
+
+    assert_eq!(format("out.graphml"), Some(Format::Graphml));
+    assert_eq!(format("out.gexf"), Some(Format::Gexf));
+    assert_eq!(format("dir/out.dot"), Some(Format::Graphviz));
+    assert_eq!(format("out.gv"), Some(Format::Graphviz));
+    assert_eq!(format("out.html"), Some(Format::Html));
+    assert_eq!(format("out.htm"), Some(Format::Html));
+    assert_eq!(format("out.mmd"), Some(Format::Mermaid));
+    assert_eq!(format("out.puml"), Some(Format::Plantuml));
+    assert_eq!(format("out.json"), Some(Format::Json));
+    assert_eq!(format("out.cql"), Some(Format::Cypher));
+    assert_eq!(format("out.svg"), None);
+    assert_eq!(format("dot"), None);
+}
@@ -0,0 +1,363 @@
+use super::Graph;
+use crate::model::{Key, Node};
+use console::Style;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Write;
+
+/// Colors for SBOMs, used in a round-robin fashion
+const COLORS: &[u8] = &[33, 34, 35, 36, 31, 32, 208, 141, 39, 148, 204, 45];
+
+/// Print the hierarchy of the graph as a tree.
+///
+/// Ancestors are shown above the top-level items of the analysis results, descendants below.
+/// Components no longer connected to any of those items (e.g. due to filtering) become top-level
+/// items themselves.
+pub struct Tree {
+    /// Maximum depth of ancestors and descendants to show
+    pub depth: Option<usize>,
+    /// Use colors for the output
+    pub color: bool,
+}
+
+/// Related components, along with the type of the relationship
+type Edges<'a> = HashMap<&'a Key, Vec<(&'a Key, &'a String)>>;
+
+struct Context<'a> {
+    graph: &'a Graph,
+    depth: Option<usize>,
+    color: bool,
+    sboms: BTreeMap<String, Style>,
+    ancestors: Edges<'a>,
+    descendants: Edges<'a>,
+    /// The components of the current branch, to stop at cycles
+    branch: HashSet<Key>,
+}
+
+impl<'a> Context<'a> {
+    fn style(&self) -> Style {
+        Style::new().force_styling(self.color)
+    }
+
+    fn label(&mut self, key: &Key, relationship: Option<&String>, bold: bool) -> String {
+        let next = self.sboms.len();
+        let color = self.color;
+        let style = self.sboms.entry(key.sbom.clone()).or_insert_with(|| {
+            Style::new()
+                .color256(COLORS[next % COLORS.len()])
+                .force_styling(color)
+        });
+
+        let style = match bold {
+            true => style.clone().bold(),
+            false => style.clone(),
+        };
+
+        let name = match self.graph.node(key) {
+            Some(node) => &node.name,
+            None => &key.node,
+        };
+
+        let mut label = style.apply_to(name).to_string();
+        if let Some(rel) = relationship {
+            label.push_str(&format!(
+                " {}",
+                self.style().dim().apply_to(format!("[{rel}]"))
+            ));
+        }
+
+        label
+    }
+
+    fn exceeds(&self, level: usize) -> bool {
+        self.depth.is_some_and(|depth| level > depth)
+    }
+
+    /// The related components, which are not part of the current branch yet
+    fn next(&self, edges: &Edges<'a>, key: &Key) -> Vec<(&'a Key, &'a String)> {
+        edges
+            .get(key)
+            .into_iter()
+            .flatten()
+            .filter(|(key, _)| !self.branch.contains(key))
+            .copied()
+            .collect()
+    }
+
+    /// Render ancestors, upwards
+    fn ancestors<W: Write>(
+        &mut self,
+        w: &mut W,
+        key: &Key,
+        prefix: &str,
+        level: usize,
+    ) -> std::io::Result<()> {
+        if self.exceeds(level) {
+            return Ok(());
+        }
+
+        for (i, (node, rel)) in self.next(&self.ancestors, key).into_iter().enumerate() {
+            let first = i == 0;
+
+            let (connector, indent) = match first {
+                true => ("┌─ ", "   "),
+                false => ("├─ ", "│  "),
+            };
+
+            self.branch.insert(node.clone());
+            self.ancestors(w, node, &format!("{prefix}{indent}"), level + 1)?;
+            self.branch.remove(node);
+            let label = self.label(node, Some(rel), false);
+            writeln!(w, "{prefix}{connector}{label}")?;
+        }
+
+        Ok(())
+    }
+
+    /// Render descendants, downwards
+    fn descendants<W: Write>(
+        &mut self,
+        w: &mut W,
+        key: &Key,
+        prefix: &str,
+        level: usize,
+    ) -> std::io::Result<()> {
+        if self.exceeds(level) {
+            return Ok(());
+        }
+
+        let nodes = self.next(&self.descendants, key);
+        for (i, &(node, rel)) in nodes.iter().enumerate() {
+            let last = i == nodes.len() - 1;
+
+            let (connector, indent) = match last {
+                true => ("└─ ", "   "),
+                false => ("├─ ", "│  "),
+            };
+
+            let label = self.label(node, Some(rel), false);
+            writeln!(w, "{prefix}{connector}{label}")?;
+            self.branch.insert(node.clone());
+            self.descendants(w, node, &format!("{prefix}{indent}"), level + 1)?;
+            self.branch.remove(node);
+        }
+
+        Ok(())
+    }
+}
+
+impl Tree {
+    pub fn render<W: Write>(&self, graph: &Graph, w: &mut W) -> std::io::Result<()> {
+        let mut ancestors = Edges::new();
+        let mut descendants = Edges::new();
+        for ((from, to), rel) in graph.sorted_relationships() {
+            ancestors.entry(to).or_default().push((from, rel));
+            descendants.entry(from).or_default().push((to, rel));
+        }
+
+        let mut context = Context {
+            graph,
+            depth: self.depth,
+            color: self.color,
+            sboms: Default::default(),
+            ancestors,
+            descendants,
+            branch: Default::default(),
+        };
+
+        for (i, root) in Self::roots(graph).into_iter().enumerate() {
+            if i > 0 {
+                writeln!(w)?;
+            }
+
+            context.branch.insert(root.clone());
+            context.ancestors(w, &root, "", 1)?;
+            let label = context.label(&root, None, true);
+            writeln!(w, "{label}")?;
+            context.descendants(w, &root, "", 1)?;
+            context.branch.remove(&root);
+        }
+
+        if !context.sboms.is_empty() {
+            writeln!(w)?;
+            writeln!(w, "{}", context.style().bold().apply_to("SBOMs:"))?;
+            for (sbom, style) in &context.sboms {
+                let Some(doc) = graph.sboms.get(sbom) else {
+                    continue;
+                };
+                writeln!(
+                    w,
+                    "  {} {} / {} / {sbom}",
+                    style.apply_to("■"),
+                    doc.document_id,
+                    doc.published
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The top-level items of the tree.
+    ///
+    /// Those are the remaining top-level items of the analysis results, followed by components
+    /// not related to any of them. Components without ancestors are picked first.
+    fn roots(graph: &Graph) -> Vec<Key> {
+        let mut result = graph.roots.iter().cloned().collect::<Vec<_>>();
+
+        let mut covered = graph.reachable(&result, true, None, |_| true);
+        covered.extend(graph.reachable(&result, false, None, |_| true));
+
+        let targets = graph
+            .relationships
+            .keys()
+            .map(|(_, to)| to)
+            .collect::<HashSet<_>>();
+        let mut candidates = graph
+            .sorted_nodes()
+            .into_iter()
+            .flat_map(|(_, nodes)| nodes)
+            .map(Node::as_key)
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|key| targets.contains(key));
+
+        for key in candidates {
+            if !covered.contains(&key) {
+                covered.extend(graph.reachable([&key], true, None, |_| true));
+                covered.extend(graph.reachable([&key], false, None, |_| true));
+                result.push(key);
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::test::{graph, node, with};
+
+    /// Add ancestors to a component, related by `dependency_of`.
+    fn above(mut node: Node, ancestors: impl IntoIterator<Item = Node>) -> Node {
+        node.ancestors
+            .extend(ancestors.into_iter().map(|mut ancestor| {
+                ancestor.relationship = Some("dependency_of".into());
+                ancestor
+            }));
+        node
+    }
+
+    fn render(graph: &Graph, depth: Option<usize>) -> String {
+        let mut output = vec![];
+        Tree {
+            depth,
+            color: false,
+        }
+        .render(graph, &mut output)
+        .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn sample() -> Graph {
+        graph([with(
+            above(
+                node("s1", "root"),
+                [
+                    above(node("s1", "p1"), [node("s1", "gp")]),
+                    node("s1", "p2"),
+                ],
+            ),
+            [with(node("s1", "a"), [node("s1", "c")]), node("s1", "b")],
+        )])
+    }
+
+    /// The lines of the expected output, followed by the SBOM legend
+    fn expected(lines: &[&str], sboms: &[&str]) -> String {
+        let mut result = lines.join("\n");
+        result.push_str("\n\nSBOMs:\n");
+        for sbom in sboms {
+            result.push_str(&format!("  ■ doc-{sbom} / 2025-01-01T00:00:00Z / {sbom}\n"));
+        }
+        result
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(
+            render(&sample(), None),
+            expected(
+                &[
+                    "   ┌─ gp [dependency_of]",
+                    "┌─ p1 [dependency_of]",
+                    "├─ p2 [dependency_of]",
+                    "root",
+                    "├─ a [dependency_of]",
+                    "│  └─ c [dependency_of]",
+                    "└─ b [dependency_of]",
+                ],
+                &["s1"]
+            )
+        );
+    }
+
+    #[test]
+    fn depth() {
+        assert_eq!(
+            render(&sample(), Some(1)),
+            expected(
+                &[
+                    "┌─ p1 [dependency_of]",
+                    "├─ p2 [dependency_of]",
+                    "root",
+                    "├─ a [dependency_of]",
+                    "└─ b [dependency_of]",
+                ],
+                &["s1"]
+            )
+        );
+    }
+
+    #[test]
+    fn cycle() {
+        let graph = graph([with(
+            node("s1", "a"),
+            [with(node("s1", "b"), [node("s1", "a")])],
+        )]);
+        assert_eq!(
+            render(&graph, None),
+            expected(
+                &["┌─ b [dependency_of]", "a", "└─ b [dependency_of]"],
+                &["s1"]
+            )
+        );
+    }
+
+    #[test]
+    fn disconnected() {
+        let mut graph = graph([
+            with(
+                node("s1", "root"),
+                [with(node("s1", "a"), [node("s1", "b")])],
+            ),
+            node("s2", "other"),
+        ]);
+        graph.retain(|key| key.node != "a");
+        assert_eq!(
+            render(&graph, None),
+            expected(&["root", "", "other", "", "b"], &["s1", "s2"])
+        );
+    }
+
+    #[test]
+    fn promote_without_ancestors() {
+        let mut graph = graph([with(
+            node("s1", "root"),
+            [with(node("s1", "a"), [node("s1", "b")])],
+        )]);
+        graph.retain(|key| key.node != "root");
+        assert_eq!(
+            render(&graph, None),
+            expected(&["a", "└─ b [dependency_of]"], &["s1"])
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use crate::model::{Node, PaginatedResult};
+use anyhow::Context;
+use std::io::{IsTerminal, Read, stdin};
+use std::path::{Path, PathBuf};
+
+/// The name of the input, reading from stdin.
+pub const STDIN: &str = "-";
+
+/// An analysis result, along with the source it was loaded from.
+#[derive(Debug)]
+pub struct Input {
+    pub source: String,
+    pub result: PaginatedResult<Node>,
+}
+
+impl Input {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if path.as_os_str() == STDIN {
+            return Self::stdin();
+        }
+
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("failed to open input: {}", path.display()))?;
+        let result = serde_json::from_reader(file)
+            .with_context(|| format!("failed to parse input: {}", path.display()))?;
+
+        Ok(Self {
+            source: path.display().to_string(),
+            result,
+        })
+    }
+
+    pub fn stdin() -> anyhow::Result<Self> {
+        let stdin = stdin();
+        if stdin.is_terminal() {
+            anyhow::bail!("stdin is a terminal, provide an input file or redirect stdin");
+        }
+
+        Self::read_stdin(stdin.lock())
+    }
+
+    fn read_stdin(reader: impl Read) -> anyhow::Result<Self> {
+        let result = serde_json::from_reader(reader).context("failed to parse input from stdin")?;
+
+        Ok(Self {
+            source: "<stdin>".to_string(),
+            result,
+        })
+    }
+}
+
+/// Expand the provided inputs into a list of files.
+///
+/// Directories are expanded into the JSON files they contain, patterns into the files (but not
+/// directories) matching them. Everything else is taken as it is.
+pub fn expand(inputs: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut result = vec![];
+
+    for input in inputs {
+        if input.as_os_str() == STDIN {
+            result.push(input.clone());
+        } else if input.is_dir() {
+            let mut files = vec![];
+            for entry in std::fs::read_dir(input)? {
+                let path = entry?.path();
+                if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+                    files.push(path);
+                }
+            }
+            files.sort();
+            result.extend(files);
+        } else if !input.exists() && is_pattern(input) {
+            let pattern = input.to_string_lossy();
+            let mut files = glob::glob(&pattern)?.collect::<Result<Vec<_>, _>>()?;
+            files.retain(|path| !path.is_dir());
+            if files.is_empty() {
+                anyhow::bail!("no input matches pattern: {pattern}");
+            }
+            result.extend(files);
+        } else {
+            result.push(input.clone());
+        }
+    }
+
+    Ok(result)
+}
+
+fn is_pattern(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?', '['])
+}
+
+/// Pair the queries with the inputs they produced, for fetching the remaining pages of truncated
+/// inputs.
+///
+/// A query belongs to the input at the same position. A single query may only be used for a
+/// single truncated input, as the others might stem from different queries.
+pub fn pair_queries(
+    inputs: usize,
+    queries: &[String],
+    truncated: usize,
+) -> anyhow::Result<Vec<Option<&String>>> {
+    Ok(match queries {
+        queries if queries.len() == inputs => queries.iter().map(Some).collect(),
+        [query] if truncated <= 1 => vec![Some(query); inputs],
+        _ if truncated == 0 => vec![None; inputs],
+        [] => vec![None; inputs],
+        queries => anyhow::bail!(
+            "{truncated} of {inputs} inputs are truncated, but --query was provided {} time(s). Provide one --query per input, in the same order, to fetch the remaining pages",
+            queries.len()
+        ),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const RESULT: &str = r#"{"items": [{"sbom_id": "s", "node_id": "a", "purl": [], "cpe": [], "name": "a", "published": "p", "document_id": "d"}], "total": 2}"#;
+
+    #[test]
+    fn load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("result.json");
+        std::fs::write(&path, RESULT).unwrap();
+
+        let input = Input::load(&path).unwrap();
+        assert_eq!(input.source, path.display().to_string());
+        assert_eq!(input.result.items[0].node_id, "a");
+        assert!(!input.result.is_complete());
+
+        let err = Input::load(&dir.path().join("missing.json")).unwrap_err();
+        assert!(err.to_string().starts_with("failed to open input"));
+
+        std::fs::write(&path, "{}").unwrap();
+        let err = Input::load(&path).unwrap_err();
+        assert!(err.to_string().starts_with("failed to parse input"));
+    }
+
+    #[test]
+    fn stdin() {
+        let input = Input::read_stdin(RESULT.as_bytes()).unwrap();
+        assert_eq!(input.source, "<stdin>");
+        assert_eq!(input.result.items.len(), 1);
+
+        let err = Input::read_stdin("".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "failed to parse input from stdin");
+    }
+
+    #[test]
+    fn expand_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["b.json", "a.json", "c.txt"] {
+            std::fs::write(dir.path().join(name), RESULT).unwrap();
+        }
+        std::fs::create_dir(dir.path().join("sub.json")).unwrap();
+        let path = |name: &str| dir.path().join(name);
+
+        // directories expand into their JSON files, sorted
+        assert_eq!(
+            expand(&[dir.path().into()]).unwrap(),
+            vec![path("a.json"), path("b.json")]
+        );
+        assert_eq!(
+            expand(&[path("*.json"), STDIN.into(), path("c.txt")]).unwrap(),
+            vec![path("a.json"), path("b.json"), STDIN.into(), path("c.txt")]
+        );
+        // files which don't exist are left to fail when loading them
+        assert_eq!(
+            expand(&[path("missing.json")]).unwrap(),
+            vec![path("missing.json")]
+        );
+        assert!(expand(&[path("*.yaml")]).is_err());
+    }
+
+    fn queries(queries: &[&str]) -> Vec<String> {
+        queries.iter().map(|query| query.to_string()).collect()
+    }
+
+    #[test]
+    fn pair_by_position() {
+        let queries = queries(&["a", "b"]);
+        let paired = pair_queries(2, &queries, 2).unwrap();
+        assert_eq!(paired, vec![Some(&queries[0]), Some(&queries[1])]);
+    }
+
+    #[test]
+    fn pair_single_query() {
+        let queries = queries(&["a"]);
+        for truncated in [0, 1] {
+            let paired = pair_queries(3, &queries, truncated).unwrap();
+            assert_eq!(paired, vec![Some(&queries[0]); 3]);
+        }
+    }
+
+    #[test]
+    fn pair_without_truncation() {
+        let queries = queries(&["a", "b"]);
+        assert_eq!(pair_queries(3, &queries, 0).unwrap(), vec![None; 3]);
+        assert_eq!(pair_queries(3, &[], 2).unwrap(), vec![None; 3]);
+    }
+
+    #[test]
+    fn pair_ambiguous() {
+        let err = pair_queries(3, &queries(&["a"]), 2).unwrap_err();
+        assert!(err.to_string().starts_with("2 of 3 inputs are truncated"));
+        let err = pair_queries(3, &queries(&["a", "b"]), 1).unwrap_err();
+        assert!(err.to_string().contains("provided 2 time(s)"));
+    }
+}
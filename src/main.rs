@@ -1,25 +1,71 @@
+mod cpe;
 mod graph;
+mod input;
 mod model;
+mod output;
+mod purl;
+mod query;
+mod server;
 
-use crate::graph::Graph;
-use crate::model::{Node, PaginatedResult};
-use anyhow::anyhow;
+use crate::graph::{Filter, Focus, Format, Graph, ImageFormat, Layout, Link, Merge, Style, Tree};
+use crate::input::Input;
+use crate::model::Node;
+use crate::output::Output;
+use crate::query::Endpoint;
+use crate::server::Server;
+use anyhow::{Context, anyhow, bail};
 use clap::Parser;
 use futures_util::{StreamExt, stream};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use reqwest::Url;
 use std::collections::BTreeSet;
-use std::io::stdout;
 use std::path::PathBuf;
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
 use urlencoding::encode;
 
 #[derive(Debug, clap::Parser)]
-#[command(version, about)]
+#[command(version, about, subcommand_precedence_over_arg = true)]
 struct Options {
+    /// Analysis response files, directories containing them, or patterns matching them.
+    ///
+    /// Use `-` to read from stdin. Without any input and query, stdin will be read as well.
     #[arg()]
-    input: PathBuf,
+    input: Vec<PathBuf>,
+
+    /// Query the analysis endpoint for a component (name, PURL or CPE), instead of reading files.
+    /// Can be repeated, combining the results.
+    ///
+    /// When used together with input files, this must be the query which produced each file, in
+    /// the same order. It will then be used to fetch the missing pages of a truncated result. A
+    /// single query may be used if only one of the files is truncated.
+    #[arg(short, long)]
+    query: Vec<String>,
+
+    /// The analysis endpoint to query
+    #[arg(short, long, value_enum, default_value_t, requires = "query")]
+    endpoint: Endpoint,
+
+    #[command(flatten)]
+    server: Server,
+
+    /// Continue with a truncated result, instead of failing
+    #[arg(long)]
+    allow_partial: bool,
+
+    #[command(flatten)]
+    filter: Filter,
+
+    #[command(flatten)]
+    focus: Focus,
+
+    #[command(flatten)]
+    merge: Merge,
+
+    #[command(flatten)]
+    link: Link,
+
+    #[command(flatten)]
+    style: Style,
 
     #[command(subcommand)]
     command: Command,
@@ -27,14 +73,102 @@ struct Options {
 
 #[derive(Debug, clap::Subcommand)]
 enum Command {
-    Graphml,
-    Gexf,
-    Graphviz,
-    Fetch {
+    Graphml {
+        #[command(flatten)]
+        output: Output,
+    },
+    Gexf {
+        #[command(flatten)]
+        output: Output,
+    },
+    Graphviz {
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Render a mermaid flowchart
+    Mermaid {
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Render a PlantUML component diagram
+    Plantuml {
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Render JSON, using the Cytoscape.js elements format
+    Json {
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Render a Cypher script, for importing into a graph database
+    Cypher {
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Render the nodes and edges as `nodes.csv` and `edges.csv`
+    Csv {
+        /// The directory to write the files to
+        #[arg(short = 'O', long, default_value = ".")]
+        output: PathBuf,
+    },
+    /// Print the analysis results as a tree, showing ancestors above and descendants below
+    Tree {
+        /// Maximum depth of ancestors and descendants to show
         #[arg(short, long)]
-        url: Url,
+        depth: Option<usize>,
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Explore the graph in an interactive terminal UI
+    Explore,
+    /// Find the paths from one component to another, selected by key (`<sbom>--<node>`), PURL or name
+    Path {
+        /// The component to start from, an ancestor of the target
+        from: String,
+        /// The component to end at, a descendant of the source
+        to: String,
+        /// Only find the given number of shortest paths
+        #[arg(short = 'k', long, default_value_t = 100)]
+        shortest: usize,
+        /// Find all paths, instead of only the shortest ones
+        #[arg(long, conflicts_with = "shortest")]
+        all: bool,
+        /// Render the graph of the paths in this format, instead of listing them
+        #[arg(short, long, value_enum)]
+        format: Option<Format>,
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Render a self-contained HTML page with an interactive viewer
+    Html {
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Render into a file, using the format matching its extension
+    Render {
+        /// The file to write, ending with `.graphml`, `.gexf`, `.dot`, `.gv`, `.html`, `.mmd`, `.puml`, `.json` or `.cypher`
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Use this format, instead of detecting it from the extension
+        #[arg(short, long, value_enum)]
+        format: Option<Format>,
+    },
+    /// Render an image, using a locally installed Graphviz
+    Image {
+        /// The file to write, ending with `.svg`, `.png` or `.pdf`
         #[arg(short, long)]
-        bearer: Option<String>,
+        output: PathBuf,
+        /// Use this format, instead of detecting it from the extension
+        #[arg(short, long, value_enum)]
+        format: Option<ImageFormat>,
+        /// The Graphviz layout engine
+        #[arg(short = 'K', long, value_enum, default_value_t)]
+        layout: Layout,
+        /// The Graphviz `dot` binary
+        #[arg(long, env = "TREEIFY_DOT", default_value = "dot")]
+        dot: PathBuf,
+    },
+    Fetch {
         #[arg(short = 'O', long)]
         output: Option<PathBuf>,
     },
@@ -43,27 +177,148 @@ enum Command {
 fn main() -> anyhow::Result<()> {
     let options = Options::parse();
 
-    let items: PaginatedResult<Node> =
-        serde_json::from_reader(std::fs::File::open(options.input)?)?;
+    let inputs = load(&options)?;
+
+    let graph = || {
+        let mut graph = Graph::new(&inputs);
+        for (key, purl, err) in graph.malformed_purls() {
+            eprintln!("WARNING: malformed PURL '{purl}' of {key}: {err}");
+        }
+        for (key, cpe, err) in graph.malformed_cpes() {
+            eprintln!("WARNING: malformed CPE '{cpe}' of {key}: {err}");
+        }
+        graph.filter(&options.filter);
+        graph.focus(&options.focus)?;
+        graph.merge(&options.merge);
+        graph.link(&options.link);
+        Ok::<_, anyhow::Error>(graph)
+    };
 
     match options.command {
-        Command::Graphviz => {
-            let graph = Graph::new(&items.items);
-            graph.render_grapviz(&mut stdout().lock())?;
+        Command::Graphviz { output } => {
+            let graph = graph()?;
+            output.write(|mut w| Ok(graph.render_grapviz(&options.style, &mut w)?))?;
+        }
+        Command::Graphml { output } => {
+            let graph = graph()?;
+            output.write(|mut w| graph.render_graphml(&options.style, &mut w))?
+        }
+        Command::Gexf { output } => {
+            let graph = graph()?;
+            output.write(|mut w| graph.render_gexf(&options.style, &mut w))?
+        }
+        Command::Mermaid { output } => {
+            let graph = graph()?;
+            output.write(|mut w| Ok(graph.render_mermaid(&mut w)?))?
+        }
+        Command::Plantuml { output } => {
+            let graph = graph()?;
+            output.write(|mut w| Ok(graph.render_plantuml(&mut w)?))?
+        }
+        Command::Json { output } => {
+            let graph = graph()?;
+            output.write(|mut w| graph.render_json(&mut w))?
+        }
+        Command::Cypher { output } => {
+            let graph = graph()?;
+            output.write(|mut w| Ok(graph.render_cypher(&mut w)?))?
+        }
+        Command::Csv { output } => {
+            let graph = graph()?;
+            std::fs::create_dir_all(&output).with_context(|| {
+                format!("failed to create output directory: {}", output.display())
+            })?;
+            output::write_file(&output.join("nodes.csv"), |mut nodes| {
+                output::write_file(&output.join("edges.csv"), |mut edges| {
+                    graph.render_csv(&mut nodes, &mut edges)
+                })
+            })?;
         }
-        Command::Graphml => {
-            let graph = Graph::new(&items.items);
-            graph.render_graphml(&mut stdout().lock())?
+        Command::Tree { depth, output } => {
+            let graph = graph()?;
+            let tree = Tree {
+                depth,
+                color: output.output.is_none() && console::colors_enabled(),
+            };
+            output.write(|mut w| Ok(tree.render(&graph, &mut w)?))?
         }
-        Command::Gexf => {
-            let graph = Graph::new(&items.items);
-            graph.render_gexf(&mut stdout().lock())?
+        Command::Explore => {
+            let graph = graph()?;
+            graph.explore()?;
         }
-        Command::Fetch {
-            url,
-            bearer,
+        Command::Path {
+            from,
+            to,
+            shortest,
+            all,
+            format,
             output,
         } => {
+            let mut graph = graph()?;
+            let paths = match all {
+                true => graph.paths(&from, &to, None)?,
+                false => {
+                    // look for one more path, to tell if there are more than requested
+                    let mut paths = graph.paths(&from, &to, Some(shortest.saturating_add(1)))?;
+                    if paths.len() > shortest {
+                        paths.truncate(shortest);
+                        eprintln!(
+                            "WARNING: there are more paths, only showing the {shortest} shortest ones. Use --shortest or --all to show more"
+                        );
+                    }
+                    paths
+                }
+            };
+            match format {
+                Some(format) => {
+                    graph.retain_paths(&paths);
+                    output.write(|mut w| graph.render(format, &options.style, &mut w))?
+                }
+                None => output.write(|mut w| Ok(graph.render_paths(&paths, &mut w)?))?,
+            }
+        }
+        Command::Html { output } => {
+            let graph = graph()?;
+            output.write(|mut w| graph.render_html(&mut w))?
+        }
+        Command::Render { output, format } => {
+            let format = match format {
+                Some(format) => format,
+                None => Format::from_path(&output).ok_or_else(|| {
+                    anyhow!(
+                        "unable to detect the format of {}, use --format",
+                        output.display()
+                    )
+                })?,
+            };
+
+            let graph = graph()?;
+            output::write_file(&output, |mut w| {
+                graph.render(format, &options.style, &mut w)
+            })?;
+        }
+        Command::Image {
+            output,
+            format,
+            layout,
+            dot,
+        } => {
+            let format = match format {
+                Some(format) => format,
+                None => ImageFormat::from_path(&output).ok_or_else(|| {
+                    anyhow!(
+                        "unable to detect the image format of {}, use --format",
+                        output.display()
+                    )
+                })?,
+            };
+
+            let graph = graph()?;
+            output::write_file(&output, |mut w| {
+                graph.render_image(&dot, layout, format, &options.style, &mut w)
+            })?;
+        }
+        Command::Fetch { output } => {
             let mut sboms = BTreeSet::new();
 
             fn scan(input: &[Node], sboms: &mut BTreeSet<String>) {
@@ -74,21 +329,81 @@ fn main() -> anyhow::Result<()> {
                 }
             }
 
-            scan(&items.items, &mut sboms);
+            for input in &inputs {
+                scan(&input.result.items, &mut sboms);
+            }
 
-            fetch(&url, output, bearer, sboms)?;
+            fetch(&options.server, output, sboms)?;
         }
     }
 
     Ok(())
 }
 
-fn fetch(
-    url: &Url,
-    output: Option<PathBuf>,
-    bearer: Option<String>,
-    sboms: BTreeSet<String>,
-) -> anyhow::Result<()> {
+fn load(options: &Options) -> anyhow::Result<Vec<Input>> {
+    let mut inputs = input::expand(&options.input)?
+        .iter()
+        .map(|path| Input::load(path))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if inputs.is_empty() {
+        if options.query.is_empty() {
+            inputs.push(Input::stdin()?);
+        }
+
+        for component in &options.query {
+            inputs.push(Input {
+                source: component.clone(),
+                result: query::query(&options.server, options.endpoint, component)?,
+            });
+        }
+    }
+
+    let truncated = inputs
+        .iter()
+        .filter(|input| !input.result.is_complete())
+        .count();
+    let queries = input::pair_queries(inputs.len(), &options.query, truncated)?;
+
+    for (input, query) in inputs.iter_mut().zip(queries) {
+        if input.result.is_complete() {
+            continue;
+        }
+
+        match query {
+            Some(component) if options.server.url.is_some() => {
+                query::fetch_remaining(
+                    &options.server,
+                    options.endpoint,
+                    component,
+                    &mut input.result,
+                )?;
+            }
+            _ if options.allow_partial => {
+                eprintln!(
+                    "WARNING: {} is truncated, only {} of {} items are present",
+                    input.source,
+                    input.result.items.len(),
+                    input.result.total
+                );
+            }
+            _ => {
+                bail!(
+                    "{} is truncated, only {} of {} items are present. Provide --query and --url to fetch the remaining pages, or use --allow-partial to continue anyway",
+                    input.source,
+                    input.result.items.len(),
+                    input.result.total
+                );
+            }
+        }
+    }
+
+    Ok(inputs)
+}
+
+fn fetch(server: &Server, output: Option<PathBuf>, sboms: BTreeSet<String>) -> anyhow::Result<()> {
+    let url = server.url()?;
+
     let rt = tokio::runtime::Runtime::new()?;
 
     let client = reqwest::Client::new();
@@ -105,7 +420,7 @@ fn fetch(
                 let pb = m.add(ProgressBar::new(0));
 
                 let client = client.clone();
-                let bearer = bearer.clone();
+                let server = server.clone();
                 let output = output.clone();
                 let m = m.clone();
 
@@ -127,13 +442,7 @@ fn fetch(
                             .progress_chars("#>-"),
                     );
 
-                    let mut req = client.get(url.clone());
-
-                    if let Some(bearer) = bearer {
-                        req = req.bearer_auth(bearer);
-                    }
-
-                    match req.send().await.and_then(|resp| resp.error_for_status()) {
+                    match server.get(&client, url.clone()).send().await.and_then(|resp| resp.error_for_status()) {
                         Ok(resp) => {
                             let total_size = resp.content_length().unwrap_or(0);
                             pb.set_length(total_size);
@@ -6,6 +6,13 @@ pub struct PaginatedResult<T> {
     pub total: usize,
 }
 
+impl<T> PaginatedResult<T> {
+    /// Check if all items reported by `total` are present.
+    pub fn is_complete(&self) -> bool {
+        self.items.len() >= self.total
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
 pub struct Node {
     pub sbom_id: String,
@@ -0,0 +1,106 @@
+use anyhow::Context;
+use std::io::{BufWriter, Write, stdout};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct Output {
+    /// Write the output to a file, instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+impl Output {
+    pub fn write<F>(&self, f: F) -> anyhow::Result<()>
+    where
+        F: FnOnce(&mut dyn Write) -> anyhow::Result<()>,
+    {
+        match &self.output {
+            Some(path) => write_file(path, f),
+            None => f(&mut stdout().lock()),
+        }
+    }
+}
+
+/// Write to a file, atomically.
+///
+/// The content is written to a temporary file next to the target first, which only replaces
+/// the target once writing succeeded. The target keeps its permissions, a new file gets the
+/// defaults (respecting the umask).
+pub fn write_file<F>(path: &Path, f: F) -> anyhow::Result<()>
+where
+    F: FnOnce(&mut dyn Write) -> anyhow::Result<()>,
+{
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut builder = tempfile::Builder::new();
+    // temporary files are only accessible by the owner by default, open it up to the umask
+    #[cfg(unix)]
+    builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o666));
+    let file = builder.tempfile_in(dir)?;
+
+    if let Ok(metadata) = std::fs::metadata(path) {
+        file.as_file().set_permissions(metadata.permissions())?;
+    }
+
+    let mut file = BufWriter::new(file);
+    f(&mut file)?;
+
+    file.into_inner()?
+        .persist(path)
+        .with_context(|| format!("failed to write output: {}", path.display()))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.txt");
+
+        write_file(&path, |w| Ok(w.write_all(b"first")?)).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first");
+
+        write_file(&path, |w| Ok(w.write_all(b"second")?)).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+    }
+
+    #[test]
+    fn keep_target_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.txt");
+        std::fs::write(&path, "original").unwrap();
+
+        let result = write_file(&path, |w| {
+            w.write_all(b"partial")?;
+            anyhow::bail!("failed")
+        });
+
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "original");
+        // no temporary files are left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keep_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.txt");
+        std::fs::write(&path, "original").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+        write_file(&path, |w| Ok(w.write_all(b"new")?)).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+}
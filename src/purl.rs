@@ -0,0 +1,112 @@
+use packageurl::PackageUrl;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// A parsed PURL, split into its components
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Purl {
+    pub r#type: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub version: Option<String>,
+    pub qualifiers: BTreeMap<String, String>,
+    pub subpath: Option<String>,
+}
+
+impl FromStr for Purl {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let purl = PackageUrl::from_str(s)?;
+
+        Ok(Self {
+            r#type: purl.ty().to_string(),
+            namespace: purl.namespace().map(ToString::to_string),
+            name: purl.name().to_string(),
+            version: purl.version().map(ToString::to_string),
+            qualifiers: purl
+                .qualifiers()
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            subpath: purl.subpath().map(ToString::to_string),
+        })
+    }
+}
+
+impl Purl {
+    /// The name, prefixed with the namespace (if present)
+    pub fn qualified_name(&self) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{namespace}/{}", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// The qualifiers, in the form of `key=value&key=value`
+    pub fn qualifiers_string(&self) -> String {
+        self.qualifiers
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let purl: Purl = "pkg:rpm/redhat/openssl@3.0.7-1.el9?arch=x86_64&distro=rhel-9#sub/path"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            purl,
+            Purl {
+                r#type: "rpm".into(),
+                namespace: Some("redhat".into()),
+                name: "openssl".into(),
+                version: Some("3.0.7-1.el9".into()),
+                qualifiers: BTreeMap::from([
+                    ("arch".into(), "x86_64".into()),
+                    ("distro".into(), "rhel-9".into()),
+                ]),
+                subpath: Some("sub/path".into()),
+            }
+        );
+        assert_eq!(purl.qualified_name(), "redhat/openssl");
+        assert_eq!(purl.qualifiers_string(), "arch=x86_64&distro=rhel-9");
+    }
+
+    #[test]
+    fn minimal() {
+        let purl: Purl = "pkg:npm/lodash".parse().unwrap();
+
+        assert_eq!(purl.r#type, "npm");
+        assert_eq!(purl.namespace, None);
+        assert_eq!(purl.name, "lodash");
+        assert_eq!(purl.qualified_name(), "lodash");
+        assert_eq!(purl.version, None);
+        assert!(purl.qualifiers.is_empty());
+        assert_eq!(purl.qualifiers_string(), "");
+        assert_eq!(purl.subpath, None);
+    }
+
+    #[test]
+    fn decode() {
+        let purl: Purl = "pkg:npm/%40scope/dep@1.0%2Bbuild".parse().unwrap();
+
+        assert_eq!(purl.namespace.as_deref(), Some("@scope"));
+        assert_eq!(purl.version.as_deref(), Some("1.0+build"));
+    }
+
+    #[test]
+    fn malformed() {
+        assert!("not a purl".parse::<Purl>().is_err());
+        assert!("pkg:maven".parse::<Purl>().is_err());
+        assert!("http://example.com".parse::<Purl>().is_err());
+    }
+}
@@ -0,0 +1,298 @@
+use crate::model::{Node, PaginatedResult};
+use crate::server::Server;
+use reqwest::{Client, Url};
+use urlencoding::encode;
+
+const PAGE_SIZE: usize = 100;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Endpoint {
+    /// `/api/v2/analysis/component`
+    #[default]
+    Component,
+    /// `/api/v2/analysis/latest/component`
+    Latest,
+}
+
+impl Endpoint {
+    fn path(&self) -> &'static str {
+        match self {
+            Self::Component => "/api/v2/analysis/component",
+            Self::Latest => "/api/v2/analysis/latest/component",
+        }
+    }
+}
+
+/// Query the analysis endpoint for a component (name, PURL or CPE), following pagination.
+pub fn query(
+    server: &Server,
+    endpoint: Endpoint,
+    component: &str,
+) -> anyhow::Result<PaginatedResult<Node>> {
+    let mut result = PaginatedResult {
+        items: vec![],
+        total: 0,
+    };
+
+    fetch_remaining(server, endpoint, component, &mut result)?;
+
+    Ok(result)
+}
+
+/// Fetch the pages following the items already present in `result`, until it is complete.
+pub fn fetch_remaining(
+    server: &Server,
+    endpoint: Endpoint,
+    component: &str,
+    result: &mut PaginatedResult<Node>,
+) -> anyhow::Result<()> {
+    let url = server.url()?.join(&format!(
+        "{path}/{key}",
+        path = endpoint.path(),
+        key = encode(component)
+    ))?;
+
+    let rt = tokio::runtime::Runtime::new()?;
+    let client = Client::new();
+
+    rt.block_on(async {
+        loop {
+            let page = fetch_page(server, &client, &url, result.items.len()).await?;
+
+            result.total = page.total;
+            if page.items.is_empty() {
+                break;
+            }
+            result.items.extend(page.items);
+
+            if result.is_complete() {
+                break;
+            }
+        }
+
+        Ok(())
+    })
+}
+
+async fn fetch_page(
+    server: &Server,
+    client: &Client,
+    url: &Url,
+    offset: usize,
+) -> anyhow::Result<PaginatedResult<Node>> {
+    let mut url = url.clone();
+    url.query_pairs_mut()
+        .append_pair("offset", &offset.to_string())
+        .append_pair("limit", &PAGE_SIZE.to_string());
+
+    let body = server
+        .get(client, url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    Ok(serde_json::from_slice(&body)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    /// The requests received by the test server, with their target and authorization header
+    type Requests = Arc<Mutex<Vec<(String, Option<String>)>>>;
+
+    /// Serve an analysis result of `available` items, claiming to have `total` of them.
+    fn serve(total: usize, available: usize) -> (Server, Requests) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Requests::default();
+
+        let received = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let target = line.split(' ').nth(1).unwrap_or_default().to_string();
+                let mut authorization = None;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':')
+                        && name.eq_ignore_ascii_case("authorization")
+                    {
+                        authorization = Some(value.trim().to_string());
+                    }
+                }
+
+                let (status, body) = match Url::parse(&format!("http://localhost{target}")) {
+                    Ok(url) if url.path().ends_with("/fail") => {
+                        ("500 Internal Server Error", String::new())
+                    }
+                    Ok(url) if url.path().starts_with("/api/v2/analysis/") => {
+                        let param = |name: &str| {
+                            url.query_pairs()
+                                .find(|(key, _)| key == name)
+                                .and_then(|(_, value)| value.parse::<usize>().ok())
+                                .unwrap_or_default()
+                        };
+                        let (offset, limit) = (param("offset"), param("limit"));
+                        let items = (offset..available.min(offset + limit))
+                            .map(|i| {
+                                serde_json::json!({
+                                    "sbom_id": "s",
+                                    "node_id": i.to_string(),
+                                    "purl": [],
+                                    "cpe": [],
+                                    "name": i.to_string(),
+                                    "published": "p",
+                                    "document_id": "d",
+                                })
+                            })
+                            .collect::<Vec<_>>();
+                        let body = serde_json::json!({ "items": items, "total": total });
+                        ("200 OK", body.to_string())
+                    }
+                    _ => ("404 Not Found", String::new()),
+                };
+                received.lock().unwrap().push((target, authorization));
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+
+        let server = Server {
+            url: Some(url.parse().unwrap()),
+            bearer: None,
+        };
+        (server, requests)
+    }
+
+    fn targets(requests: &Requests) -> Vec<String> {
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(target, _)| target.clone())
+            .collect()
+    }
+
+    #[test]
+    fn query_component() {
+        let (server, requests) = serve(2, 2);
+
+        let result = query(&server, Endpoint::Component, "pkg:npm/a@1").unwrap();
+
+        assert_eq!(result.total, 2);
+        assert_eq!(result.items.len(), 2);
+        assert_eq!(
+            targets(&requests),
+            vec!["/api/v2/analysis/component/pkg%3Anpm%2Fa%401?offset=0&limit=100"]
+        );
+        assert_eq!(requests.lock().unwrap()[0].1, None);
+    }
+
+    #[test]
+    fn query_latest() {
+        let (server, requests) = serve(0, 0);
+        let server = Server {
+            bearer: Some("token".into()),
+            ..server
+        };
+
+        let result = query(&server, Endpoint::Latest, "openssl").unwrap();
+
+        assert!(result.items.is_empty());
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![(
+                "/api/v2/analysis/latest/component/openssl?offset=0&limit=100".to_string(),
+                Some("Bearer token".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn query_errors() {
+        let server = Server {
+            url: None,
+            bearer: None,
+        };
+        let err = query(&server, Endpoint::Component, "a").unwrap_err();
+        assert_eq!(err.to_string(), "no server URL provided, use --url");
+
+        let (server, _) = serve(0, 0);
+        let err = query(&server, Endpoint::Component, "fail").unwrap_err();
+        assert!(
+            err.to_string().contains("500 Internal Server Error"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn pagination() {
+        let (server, requests) = serve(250, 250);
+
+        let result = query(&server, Endpoint::Component, "a").unwrap();
+
+        assert_eq!(result.total, 250);
+        assert_eq!(
+            result
+                .items
+                .iter()
+                .map(|node| node.node_id.clone())
+                .collect::<Vec<_>>(),
+            (0..250).map(|i| i.to_string()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            targets(&requests),
+            vec![
+                "/api/v2/analysis/component/a?offset=0&limit=100",
+                "/api/v2/analysis/component/a?offset=100&limit=100",
+                "/api/v2/analysis/component/a?offset=200&limit=100",
+            ]
+        );
+    }
+
+    #[test]
+    fn fetch_missing_pages() {
+        let (server, requests) = serve(150, 150);
+        let mut result = query(&serve(150, 100).0, Endpoint::Component, "a").unwrap();
+        assert!(!result.is_complete());
+
+        fetch_remaining(&server, Endpoint::Component, "a", &mut result).unwrap();
+
+        assert!(result.is_complete());
+        assert_eq!(result.items.len(), 150);
+        assert_eq!(
+            targets(&requests),
+            vec!["/api/v2/analysis/component/a?offset=100&limit=100"]
+        );
+    }
+
+    #[test]
+    fn stop_at_empty_page() {
+        let (server, requests) = serve(150, 120);
+
+        let result = query(&server, Endpoint::Component, "a").unwrap();
+
+        assert!(!result.is_complete());
+        assert_eq!(result.items.len(), 120);
+        assert_eq!(targets(&requests).len(), 3);
+    }
+}
@@ -0,0 +1,29 @@
+use anyhow::anyhow;
+use reqwest::{Client, RequestBuilder, Url};
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct Server {
+    /// Base URL of the Trustify server
+    #[arg(short, long, global = true)]
+    pub url: Option<Url>,
+    /// Bearer token used to authenticate with the server
+    #[arg(short, long, global = true)]
+    pub bearer: Option<String>,
+}
+
+impl Server {
+    pub fn url(&self) -> anyhow::Result<&Url> {
+        self.url
+            .as_ref()
+            .ok_or_else(|| anyhow!("no server URL provided, use --url"))
+    }
+
+    pub fn get(&self, client: &Client, url: Url) -> RequestBuilder {
+        let req = client.get(url);
+
+        match &self.bearer {
+            Some(bearer) => req.bearer_auth(bearer),
+            None => req,
+        }
+    }
+}